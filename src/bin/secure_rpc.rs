@@ -29,12 +29,22 @@ use radius_sdk::{
     util::{get_resource_limit, set_resource_limit, ResourceType},
 };
 use secure_rpc::{
-    client::distributed_key_generation::DistributedKeyGenerationClient,
+    client::{
+        distributed_key_generation::DistributedKeyGenerationClient,
+        rollup::{RollupClient, RollupUpstream},
+        sequencer::{RetryPolicy, SequencerClient},
+        upstream_health::HealthPolicy,
+    },
     error::Error,
-    rpc::{eth, *},
+    gateway::{ApiKeyList, Gateway, Passthrough, Quota, RateLimiter, RollupRouter, WebSocketProxy},
+    journal::SubmissionJournal,
+    monitoring,
+    rpc::register_internal_methods,
     state::{AppState, PvdeParams},
+    task::{encryption_key_refresher, rollup_health_checker, sequencer_health_checker},
     types::config::{Config, ConfigOption, ConfigPath},
 };
+use sequencer::types::EncryptedTransactionType;
 use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
 
@@ -94,16 +104,116 @@ async fn main() -> Result<(), Error> {
             set_resource_limit(ResourceType::RLIMIT_NOFILE, rlimit.hard_limit)?;
 
             let config = Config::load(config_option)?;
-            let config_path = config_option.path.clone();
 
             tracing::info!("Successfully loaded the configuration file.",);
 
-            let is_using_zkp = config.is_using_zkp();
+            let rollup_profile_list = if config.is_using_rollup_profiles() {
+                let rollup_profile_list = Config::load_rollup_profiles(config_option)?;
+
+                tracing::info!(
+                    "Successfully loaded the rollup profiles: {:?}",
+                    config.rollup_profiles_path()
+                );
+
+                rollup_profile_list
+            } else {
+                Vec::new()
+            };
+
+            // Every rollup shares the submission journal, the rate limiter and
+            // the api keys of the configured rollup.
+            let submission_journal_path = config.submission_journal_path();
+            let submission_journal = SubmissionJournal::open(
+                &submission_journal_path,
+                config.submission_journal_retention(),
+                config.submission_journal_capacity(),
+            )?;
+
+            tracing::info!(
+                "Successfully opened the submission journal: {:?}",
+                submission_journal_path
+            );
+
+            let rate_limiter = initialize_rate_limiter(&config);
+            let api_key_list = initialize_api_key_list(&config)?;
+
+            let external_rpc_url = anywhere(&config.external_port()?);
+            let rollup_id = config.rollup_id().clone();
+
+            let (app_state, server_handle) =
+                initialize_rollup(config, submission_journal.clone()).await?;
+            let mut server_handle_list = vec![server_handle];
+            let mut app_state_list = vec![app_state.as_ref().clone()];
+
+            // Initialize the gateway of every rollup.
+            let mut rollup_router = RollupRouter::new(
+                &rollup_id,
+                initialize_gateway(&app_state, rate_limiter.clone(), api_key_list.clone()),
+            );
+
+            for rollup_profile in rollup_profile_list {
+                let rollup_id = rollup_profile.config.rollup_id().clone();
+
+                let (profile_app_state, server_handle) =
+                    initialize_rollup(rollup_profile.config, submission_journal.clone()).await?;
+                server_handle_list.push(server_handle);
+                app_state_list.push(profile_app_state.as_ref().clone());
+
+                rollup_router.add(
+                    &rollup_id,
+                    rollup_profile.host,
+                    initialize_gateway(
+                        &profile_app_state,
+                        rate_limiter.clone(),
+                        api_key_list.clone(),
+                    ),
+                );
+
+                tracing::info!("Successfully initialized the rollup: {:?}", rollup_id);
+            }
+
+            let gateway_handle = rollup_router.serve(&external_rpc_url).await?;
+            tracing::info!("Successfully started the gateway: {}", external_rpc_url);
+
+            // Initialize the monitoring server.
+            let monitoring_url = anywhere(&app_state.config().metrics_port()?);
+            monitoring::serve(app_state_list, &monitoring_url).await?;
+            tracing::info!(
+                "Successfully started the monitoring server: {}",
+                monitoring_url
+            );
+
+            for server_handle in server_handle_list {
+                server_handle.await.unwrap();
+            }
+            gateway_handle.await.unwrap();
+        }
+    }
 
+    Ok(())
+}
+
+/// Initializes the clients, background tasks and internal RPC server of a
+/// rollup.
+async fn initialize_rollup(
+    config: Config,
+    submission_journal: SubmissionJournal,
+) -> Result<(Arc<AppState>, JoinHandle<()>), Error> {
+    let is_using_zkp = config.is_using_zkp();
+    let pvde_params_path = config.pvde_params_path();
+
+    // The distributed key generation committee is only required for SKDE.
+    let (skde_params, distributed_key_generation_client) = match config.encrypted_transaction_type()
+    {
+        EncryptedTransactionType::Skde => {
             let distributed_key_generation_rpc_url = config.distributed_key_generation_rpc_url();
-            let distributed_key_generation_client =
-                DistributedKeyGenerationClient::new(distributed_key_generation_rpc_url)
-                    .map_err(Error::DistributedKeyGenerationClient)?;
+            let distributed_key_generation_client = DistributedKeyGenerationClient::new(
+                config.rollup_id().clone(),
+                distributed_key_generation_rpc_url,
+                config.key_cache_capacity(),
+                config.unavailable_key_ttl(),
+            )
+            .map_err(Error::DistributedKeyGenerationClient)?;
 
             tracing::info!("Successfully initialize distributed key generation client.");
 
@@ -115,77 +225,169 @@ async fn main() -> Result<(), Error> {
 
             tracing::info!("Complete to skde params: {:?}", skde_params);
 
-            let app_state = Arc::new(AppState::new(
-                config,
-                skde_params,
-                Some(distributed_key_generation_client),
-            ));
-
-            // Initialize the secure RPC server.
-            let server_handle = initialize_external_rpc_server(&app_state).await?;
-
-            if let Some(path) = config_path {
-                // Initialize the time lock puzzle parameters.
-                store_time_lock_puzzle_param(app_state, path, is_using_zkp).await?;
-            }
+            (Some(skde_params), Some(distributed_key_generation_client))
+        }
+        _ => (None, None),
+    };
 
-            server_handle.await.unwrap();
+    let sequencer_client = SequencerClient::new(
+        config.rollup_id().clone(),
+        config.sequencer_rpc_url_list().clone(),
+        RetryPolicy {
+            max_attempts: config.sequencer_max_attempts(),
+            attempt_timeout: config.sequencer_attempt_timeout(),
+            backoff: config.sequencer_retry_backoff(),
+        },
+        HealthPolicy {
+            unhealthy_threshold: config.sequencer_unhealthy_threshold(),
+            healthy_threshold: config.sequencer_healthy_threshold(),
+        },
+    )
+    .map_err(Error::Sequencer)?;
+
+    let rollup_client = RollupClient::new(
+        config.rollup_id().clone(),
+        config
+            .rollup_rpc_url_list()
+            .iter()
+            .enumerate()
+            .map(|(index, rpc_url)| RollupUpstream {
+                rpc_url: rpc_url.clone(),
+                weight: config
+                    .rollup_rpc_weight_list()
+                    .get(index)
+                    .copied()
+                    .unwrap_or(1),
+            })
+            .collect(),
+        config.rollup_selection(),
+        HealthPolicy {
+            unhealthy_threshold: config.rollup_unhealthy_threshold(),
+            healthy_threshold: config.rollup_healthy_threshold(),
+        },
+        config.rollup_max_block_lag(),
+        config.rollup_attempt_timeout(),
+    )?;
+
+    let app_state = Arc::new(AppState::new(
+        config,
+        skde_params,
+        distributed_key_generation_client,
+        sequencer_client,
+        rollup_client,
+        submission_journal,
+    ));
+
+    if app_state.distributed_key_generation_client().is_some() {
+        // Prime the encryption key cache and keep it fresh in the background.
+        if let Err(error) = encryption_key_refresher::refresh(&app_state).await {
+            tracing::warn!("Failed to fetch the initial encryption key: {:?}", error);
         }
+        tokio::spawn(encryption_key_refresher::run(app_state.as_ref().clone()));
     }
 
-    Ok(())
+    tokio::spawn(sequencer_health_checker::run(app_state.as_ref().clone()));
+    tokio::spawn(rollup_health_checker::run(app_state.as_ref().clone()));
+
+    // Initialize the time lock puzzle parameters before serving PVDE requests.
+    store_time_lock_puzzle_param(app_state.clone(), pvde_params_path, is_using_zkp).await?;
+
+    // Initialize the secure RPC server.
+    let server_handle = initialize_internal_rpc_server(&app_state).await?;
+
+    Ok((app_state, server_handle))
 }
 
-async fn initialize_external_rpc_server(
+async fn initialize_internal_rpc_server(
     context: &AppState, // rpc_client: &RpcClient,
 ) -> Result<JoinHandle<()>, Error> {
-    let external_rpc_url = anywhere(&context.config().external_port()?);
-
-    // Initialize the external RPC server.
-    let external_rpc_server = RpcServer::new(context.clone())
-        // eth
-        .register_rpc_method::<eth::EthBlockNumber>()?
-        .register_rpc_method::<eth::EthCall>()?
-        .register_rpc_method::<eth::EthChainId>()?
-        .register_rpc_method::<eth::EthEstimateGas>()?
-        .register_rpc_method::<eth::EthFeeHistory>()?
-        .register_rpc_method::<eth::EthGasPrice>()?
-        .register_rpc_method::<eth::EthGetBalance>()?
-        .register_rpc_method::<eth::EthGetBlockByHash>()?
-        .register_rpc_method::<eth::EthGetBlockByNumber>()?
-        .register_rpc_method::<eth::EthGetCode>()?
-        .register_rpc_method::<eth::EthGetTransactionByHash>()?
-        .register_rpc_method::<eth::EthGetTransactionCount>()?
-        .register_rpc_method::<eth::EthGetTransactionReceipt>()?
-        .register_rpc_method::<eth::EthNetVersion>()?
-        .register_rpc_method::<eth::EthSendRawTransaction>()?
-        // cryptography
-        .register_rpc_method::<DecryptTransaction>()?
-        .register_rpc_method::<EncryptTransaction>()?
-        // sequencer
-        .register_rpc_method::<SendEncryptedTransaction>()?
-        .register_rpc_method::<SendRawTransaction>()?
-        .init(external_rpc_url.clone())
+    let internal_rpc_url = local(&context.config().internal_port()?);
+
+    // Initialize the internal RPC server.
+    let internal_rpc_server = register_internal_methods(RpcServer::new(context.clone()))?
+        .init(internal_rpc_url.clone())
         .await?;
 
     tracing::info!(
         "Successfully started the Secure RPC server: {}",
-        external_rpc_url
+        internal_rpc_url
     );
 
     let server_handle = tokio::spawn(async move {
-        external_rpc_server.stopped().await;
+        internal_rpc_server.stopped().await;
     });
 
     Ok(server_handle)
 }
 
+fn initialize_rate_limiter(config: &Config) -> Option<RateLimiter> {
+    config.is_using_rate_limit().then(|| {
+        RateLimiter::new(
+            Quota {
+                requests_per_second: config.rate_limit_read_requests_per_second(),
+                burst: config.rate_limit_read_burst(),
+            },
+            Quota {
+                requests_per_second: config.rate_limit_expensive_requests_per_second(),
+                burst: config.rate_limit_expensive_burst(),
+            },
+            config.is_rate_limiting_by_api_key(),
+        )
+    })
+}
+
+fn initialize_api_key_list(config: &Config) -> Result<Option<ApiKeyList>, Error> {
+    if !config.is_using_api_key_auth() {
+        return Ok(None);
+    }
+
+    let api_keys_path = config.api_keys_path();
+    let api_key_list = ApiKeyList::load(&api_keys_path)?;
+
+    tracing::info!("Successfully loaded the api keys: {:?}", api_keys_path);
+
+    Ok(Some(api_key_list))
+}
+
+fn initialize_gateway(
+    context: &AppState,
+    rate_limiter: Option<RateLimiter>,
+    api_key_list: Option<ApiKeyList>,
+) -> Gateway {
+    let websocket_proxy = context
+        .config()
+        .is_using_websocket()
+        .then(|| WebSocketProxy {
+            rollup_websocket_url: context.config().rollup_websocket_url().to_owned(),
+            max_subscriptions: context.config().websocket_max_subscriptions(),
+        });
+
+    let passthrough = context
+        .config()
+        .is_using_passthrough()
+        .then(|| Passthrough {
+            allow_pattern_list: context.config().passthrough_allow_list().clone(),
+            deny_pattern_list: context.config().passthrough_deny_list().clone(),
+        });
+
+    Gateway::new(
+        context.clone(),
+        context.config().max_batch_size(),
+        rate_limiter,
+        api_key_list,
+        websocket_proxy,
+        passthrough,
+    )
+}
+
 pub async fn store_time_lock_puzzle_param(
     app_state: Arc<AppState>,
-    config_path: PathBuf,
+    pvde_params_path: PathBuf,
     is_using_zkp: bool,
 ) -> Result<(), Error> {
-    let time_lock_puzzle_param_path = config_path
+    fs::create_dir_all(&pvde_params_path)?;
+
+    let time_lock_puzzle_param_path = pvde_params_path
         .join("time_lock_puzzle_param.json")
         .to_str()
         .unwrap()
@@ -203,17 +405,17 @@ pub async fn store_time_lock_puzzle_param(
     pvde_params.update_time_lock_puzzle_param(time_lock_puzzle_param);
 
     if is_using_zkp {
-        let key_validation_param_file_path = config_path
+        let key_validation_param_file_path = pvde_params_path
             .join("key_validation_zkp_param.data")
             .to_str()
             .unwrap()
             .to_string();
-        let key_validation_proving_key_file_path = config_path
+        let key_validation_proving_key_file_path = pvde_params_path
             .join("key_validation_proving_key.data")
             .to_str()
             .unwrap()
             .to_string();
-        let key_validation_verifying_key_file_path = config_path
+        let key_validation_verifying_key_file_path = pvde_params_path
             .join("key_validation_verifying_key.data")
             .to_str()
             .unwrap()
@@ -247,17 +449,17 @@ pub async fn store_time_lock_puzzle_param(
         pvde_params.update_key_validation_proving_key(key_validation_proving_key);
         pvde_params.update_key_validation_verifying_key(key_validation_verifying_key);
 
-        let poseidon_encryption_param_file_path = config_path
+        let poseidon_encryption_param_file_path = pvde_params_path
             .join("poseidon_encryption_param.json")
             .to_str()
             .unwrap()
             .to_string();
-        let poseidon_encryption_proving_key_file_path = config_path
+        let poseidon_encryption_proving_key_file_path = pvde_params_path
             .join("poseidon_encryption_proving_key.data")
             .to_str()
             .unwrap()
             .to_string();
-        let poseidon_encryption_verifying_key_file_path = config_path
+        let poseidon_encryption_verifying_key_file_path = pvde_params_path
             .join("poseidon_encryption_verifying_key.data")
             .to_str()
             .unwrap()
@@ -295,19 +497,23 @@ pub async fn store_time_lock_puzzle_param(
         pvde_params.update_poseidon_encryption_zkp_param(poseidon_encryption_zkp_param);
         pvde_params.update_poseidon_encryption_proving_key(poseidon_encryption_proving_key);
         pvde_params.update_poseidon_encryption_verifying_key(poseidon_encryption_verifying_key);
-
-        app_state
-            .pvde_params()
-            .update(Some(pvde_params))
-            .map_err(|error| {
-                tracing::error!("Failed to update the PVDE parameters: {:?}", error);
-                Error::ContextUpdateFail
-            })?;
     }
 
+    app_state
+        .pvde_params()
+        .update(Some(pvde_params))
+        .map_err(|error| {
+            tracing::error!("Failed to update the PVDE parameters: {:?}", error);
+            Error::ContextUpdateFail
+        })?;
+
     Ok(())
 }
 
 pub fn anywhere(port: &str) -> String {
     format!("0.0.0.0:{}", port)
 }
+
+pub fn local(port: &str) -> String {
+    format!("127.0.0.1:{}", port)
+}
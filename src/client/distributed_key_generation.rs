@@ -1,15 +1,29 @@
-use std::sync::Arc;
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use radius_sdk::json_rpc::client::{Id, RpcClient, RpcClientError};
-use serde::{Deserialize, Serialize};
+use lru::LruCache;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::sequencer::JsonRpcErrorObject;
+use crate::metrics::metrics;
+
+/// How long a request to the distributed key generation node may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct DistributedKeyGenerationClient {
     inner: Arc<DistributedKeyGenerationClientInner>,
 }
 
 struct DistributedKeyGenerationClientInner {
+    rollup_id: String,
     rpc_url: String,
-    rpc_client: RpcClient,
+    http_client: reqwest::Client,
+    encryption_key_cache: KeyCache,
+    decryption_key_cache: KeyCache,
 }
 
 impl Clone for DistributedKeyGenerationClient {
@@ -21,10 +35,26 @@ impl Clone for DistributedKeyGenerationClient {
 }
 
 impl DistributedKeyGenerationClient {
-    pub fn new(rpc_url: impl AsRef<str>) -> Result<Self, RpcClientError> {
+    /// Caches up to `key_cache_capacity` encryption and decryption keys each,
+    /// and remembers for `unavailable_key_ttl` that the node has not released a
+    /// key yet.
+    pub fn new(
+        rollup_id: String,
+        rpc_url: impl AsRef<str>,
+        key_cache_capacity: usize,
+        unavailable_key_ttl: Duration,
+    ) -> Result<Self, DistributedKeyGenerationClientError> {
+        let http_client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(DistributedKeyGenerationClientError::BuildClient)?;
+
         let inner = DistributedKeyGenerationClientInner {
+            rollup_id,
             rpc_url: rpc_url.as_ref().to_owned(),
-            rpc_client: RpcClient::new()?,
+            http_client,
+            encryption_key_cache: KeyCache::new(key_cache_capacity, unavailable_key_ttl),
+            decryption_key_cache: KeyCache::new(key_cache_capacity, unavailable_key_ttl),
         };
 
         Ok(Self {
@@ -34,69 +64,300 @@ impl DistributedKeyGenerationClient {
 
     pub async fn get_latest_encryption_key(
         &self,
-    ) -> Result<GetLatestEncryptionKeyReturn, RpcClientError> {
+    ) -> Result<GetLatestEncryptionKeyReturn, DistributedKeyGenerationClientError> {
         let parameter = GetLatestEncryptionKey {};
 
+        let response: GetLatestEncryptionKeyReturn = self
+            .request(GetLatestEncryptionKey::METHOD_NAME, &parameter)
+            .await?;
+
         self.inner
-            .rpc_client
-            .request(
-                &self.inner.rpc_url,
-                GetLatestEncryptionKey::METHOD_NAME,
-                &parameter,
-                Id::Null,
-            )
-            .await
+            .encryption_key_cache
+            .insert(response.key_id, response.encryption_key.clone());
+
+        Ok(response)
     }
 
     pub async fn get_encryption_key(
         &self,
         key_id: u64,
-    ) -> Result<GetEncryptionKeyReturn, RpcClientError> {
+    ) -> Result<GetEncryptionKeyReturn, DistributedKeyGenerationClientError> {
+        match self.inner.encryption_key_cache.get(key_id) {
+            KeyCacheLookup::Hit(encryption_key) => {
+                return Ok(GetEncryptionKeyReturn { encryption_key })
+            }
+            KeyCacheLookup::Unavailable => {
+                return Err(DistributedKeyGenerationClientError::KeyNotAvailable(key_id))
+            }
+            KeyCacheLookup::Miss => {}
+        }
+
         let parameter = GetEncryptionKey { key_id };
 
-        self.inner
-            .rpc_client
-            .request(
-                &self.inner.rpc_url,
-                GetEncryptionKey::METHOD_NAME,
-                &parameter,
-                Id::Null,
-            )
-            .await
+        let response: Result<GetEncryptionKeyReturn, DistributedKeyGenerationClientError> = self
+            .request(GetEncryptionKey::METHOD_NAME, &parameter)
+            .await;
+
+        match response {
+            Ok(response) => {
+                self.inner
+                    .encryption_key_cache
+                    .insert(key_id, response.encryption_key.clone());
+
+                Ok(response)
+            }
+            Err(error) => {
+                if error.is_key_unavailable() {
+                    self.inner.encryption_key_cache.mark_unavailable(key_id);
+                }
+
+                Err(error)
+            }
+        }
     }
 
+    /// Returns the decryption key for `key_id`.
+    ///
+    /// A released decryption key never changes, so it is served from the cache
+    /// afterwards. The node refusing a key is remembered for a short while so
+    /// that bulk decryption of not-yet-released keys does not hammer the node,
+    /// while transport failures are retried on the next call.
     pub async fn get_decryption_key(
         &self,
         key_id: u64,
-    ) -> Result<GetDecryptionKeyResponse, RpcClientError> {
+    ) -> Result<GetDecryptionKeyResponse, DistributedKeyGenerationClientError> {
+        match self.inner.decryption_key_cache.get(key_id) {
+            KeyCacheLookup::Hit(decryption_key) => {
+                return Ok(GetDecryptionKeyResponse { decryption_key })
+            }
+            KeyCacheLookup::Unavailable => {
+                return Err(DistributedKeyGenerationClientError::KeyNotAvailable(key_id))
+            }
+            KeyCacheLookup::Miss => {}
+        }
+
         let parameter = GetDecryptionKey { key_id };
 
-        self.inner
-            .rpc_client
-            .request(
-                &self.inner.rpc_url,
-                GetDecryptionKey::METHOD_NAME,
-                &parameter,
-                Id::Null,
-            )
-            .await
+        let response: Result<GetDecryptionKeyResponse, DistributedKeyGenerationClientError> = self
+            .request(GetDecryptionKey::METHOD_NAME, &parameter)
+            .await;
+
+        match response {
+            Ok(response) => {
+                self.inner
+                    .decryption_key_cache
+                    .insert(key_id, response.decryption_key.clone());
+
+                Ok(response)
+            }
+            Err(error) => {
+                if error.is_key_unavailable() {
+                    self.inner.decryption_key_cache.mark_unavailable(key_id);
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn request<P, R>(
+        &self,
+        method: &'static str,
+        parameter: &P,
+    ) -> Result<R, DistributedKeyGenerationClientError>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let started_at = Instant::now();
+
+        let response = self.send(method, parameter).await;
+
+        metrics().observe_distributed_key_generation_request(
+            &self.inner.rollup_id,
+            method,
+            response.is_ok(),
+            started_at.elapsed(),
+        );
+
+        response
     }
 
-    pub async fn get_skde_params(&self) -> Result<GetSkdeParamsResponse, RpcClientError> {
+    pub async fn get_skde_params(
+        &self,
+    ) -> Result<GetSkdeParamsResponse, DistributedKeyGenerationClientError> {
         let parameter = GetSkdeParams {};
 
-        self.inner
-            .rpc_client
-            .request(
-                &self.inner.rpc_url,
-                GetSkdeParams::METHOD_NAME,
-                &parameter,
-                Id::Null,
-            )
+        self.request(GetSkdeParams::METHOD_NAME, &parameter).await
+    }
+
+    async fn send<P, R>(
+        &self,
+        method: &str,
+        parameter: &P,
+    ) -> Result<R, DistributedKeyGenerationClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            method,
+            params: parameter,
+            id: 1,
+        };
+
+        let response = self
+            .inner
+            .http_client
+            .post(&self.inner.rpc_url)
+            .json(&request)
+            .send()
             .await
+            .map_err(DistributedKeyGenerationClientError::Transport)?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(DistributedKeyGenerationClientError::ServerError(
+                status.as_u16(),
+            ));
+        }
+
+        let response: JsonRpcResponse = response.json().await.map_err(|error| {
+            DistributedKeyGenerationClientError::InvalidResponse(error.to_string())
+        })?;
+
+        if let Some(error) = response.error {
+            return Err(DistributedKeyGenerationClientError::Rejected(error));
+        }
+
+        serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(|error| {
+            DistributedKeyGenerationClientError::InvalidResponse(error.to_string())
+        })
+    }
+}
+
+/// Fragments of the normalized error messages of the node for a key it has not
+/// generated or released yet.
+const KEY_UNAVAILABLE_MESSAGE_LIST: [&str; 3] = ["notfound", "notavailable", "notexist"];
+
+/// Bounded LRU cache of keys by key id with negative caching of keys the node
+/// has not released yet.
+struct KeyCache {
+    keys: Mutex<LruCache<u64, String>>,
+    unavailable_keys: Mutex<LruCache<u64, Instant>>,
+    unavailable_key_ttl: Duration,
+}
+
+enum KeyCacheLookup {
+    Hit(String),
+    Unavailable,
+    Miss,
+}
+
+impl KeyCache {
+    fn new(capacity: usize, unavailable_key_ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+
+        Self {
+            keys: Mutex::new(LruCache::new(capacity)),
+            unavailable_keys: Mutex::new(LruCache::new(capacity)),
+            unavailable_key_ttl,
+        }
+    }
+
+    fn get(&self, key_id: u64) -> KeyCacheLookup {
+        if let Some(key) = self.keys.lock().unwrap().get(&key_id) {
+            return KeyCacheLookup::Hit(key.clone());
+        }
+
+        let mut unavailable_keys = self.unavailable_keys.lock().unwrap();
+        match unavailable_keys.get(&key_id) {
+            Some(marked_at) if marked_at.elapsed() < self.unavailable_key_ttl => {
+                KeyCacheLookup::Unavailable
+            }
+            Some(_) => {
+                unavailable_keys.pop(&key_id);
+                KeyCacheLookup::Miss
+            }
+            None => KeyCacheLookup::Miss,
+        }
+    }
+
+    fn insert(&self, key_id: u64, key: String) {
+        self.unavailable_keys.lock().unwrap().pop(&key_id);
+        self.keys.lock().unwrap().put(key_id, key);
+    }
+
+    fn mark_unavailable(&self, key_id: u64) {
+        self.unavailable_keys
+            .lock()
+            .unwrap()
+            .put(key_id, Instant::now());
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    result: Option<Value>,
+    error: Option<JsonRpcErrorObject>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a, P> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: &'a P,
+    id: u64,
+}
+
+#[derive(Debug)]
+pub enum DistributedKeyGenerationClientError {
+    BuildClient(reqwest::Error),
+    /// The node could not be reached or did not answer in time.
+    Transport(reqwest::Error),
+    ServerError(u16),
+    InvalidResponse(String),
+    /// The node answered with a JSON-RPC error, such as for a key it has not
+    /// released yet.
+    Rejected(JsonRpcErrorObject),
+    /// The key was recently reported as unavailable and is negatively cached.
+    KeyNotAvailable(u64),
+}
+
+impl DistributedKeyGenerationClientError {
+    /// Returns `true` if the node answered that the key is not available, as
+    /// opposed to failing to answer at all or rejecting the request otherwise.
+    pub fn is_key_unavailable(&self) -> bool {
+        match self {
+            Self::Rejected(error) => {
+                // The node reports missing keys as a store lookup failure, worded
+                // as "not found", "NotFound" or "not available".
+                let message: String = error
+                    .message
+                    .chars()
+                    .filter(char::is_ascii_alphanumeric)
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect();
+
+                KEY_UNAVAILABLE_MESSAGE_LIST
+                    .iter()
+                    .any(|fragment| message.contains(fragment))
+            }
+            Self::KeyNotAvailable(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for DistributedKeyGenerationClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
     }
 }
 
+impl std::error::Error for DistributedKeyGenerationClientError {}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GetLatestEncryptionKey {}
 
@@ -149,3 +410,77 @@ impl GetSkdeParams {
 pub struct GetSkdeParamsResponse {
     pub skde_params: skde::delay_encryption::SkdeParams,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_cached_keys() {
+        let key_cache = KeyCache::new(2, Duration::from_secs(60));
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Miss));
+
+        key_cache.insert(1, "key-1".into());
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Hit(key) if key == "key-1"));
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_key() {
+        let key_cache = KeyCache::new(2, Duration::from_secs(60));
+        key_cache.insert(1, "key-1".into());
+        key_cache.insert(2, "key-2".into());
+
+        // Key 1 is used after key 2, so key 2 makes room for key 3.
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Hit(_)));
+        key_cache.insert(3, "key-3".into());
+
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Hit(_)));
+        assert!(matches!(key_cache.get(2), KeyCacheLookup::Miss));
+        assert!(matches!(key_cache.get(3), KeyCacheLookup::Hit(_)));
+    }
+
+    #[test]
+    fn expires_unavailable_keys() {
+        let key_cache = KeyCache::new(2, Duration::from_millis(20));
+
+        key_cache.mark_unavailable(1);
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Unavailable));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(matches!(key_cache.get(1), KeyCacheLookup::Miss));
+
+        // A released key replaces the unavailable mark.
+        key_cache.mark_unavailable(2);
+        key_cache.insert(2, "key-2".into());
+        assert!(matches!(key_cache.get(2), KeyCacheLookup::Hit(_)));
+    }
+
+    #[test]
+    fn caches_only_answered_unavailability() {
+        let rejected = DistributedKeyGenerationClientError::Rejected(JsonRpcErrorObject {
+            code: -32603,
+            message: "key not available".into(),
+            data: None,
+        });
+        assert!(rejected.is_key_unavailable());
+
+        let not_found = DistributedKeyGenerationClientError::Rejected(JsonRpcErrorObject {
+            code: -32603,
+            message: "KvStore(NotFound)".into(),
+            data: None,
+        });
+        assert!(not_found.is_key_unavailable());
+
+        let invalid_params = DistributedKeyGenerationClientError::Rejected(JsonRpcErrorObject {
+            code: -32602,
+            message: "invalid type: string, expected u64".into(),
+            data: None,
+        });
+        assert!(!invalid_params.is_key_unavailable());
+
+        let invalid_response =
+            DistributedKeyGenerationClientError::InvalidResponse("timeout".into());
+        assert!(!invalid_response.is_key_unavailable());
+        assert!(!DistributedKeyGenerationClientError::ServerError(502).is_key_unavailable());
+    }
+}
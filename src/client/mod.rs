@@ -1 +1,4 @@
 pub mod distributed_key_generation;
+pub mod rollup;
+pub mod sequencer;
+pub mod upstream_health;
@@ -0,0 +1,598 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    client::{
+        sequencer::JsonRpcErrorObject,
+        upstream_health::{HealthPolicy, UpstreamHealth},
+    },
+    metrics::metrics,
+    types::RollupSelection,
+};
+
+/// The metric label of batch requests to the rollup.
+const BATCH_METHOD: &str = "batch";
+
+/// Sent to probe a rollup rpc url, which also tells how far behind it is.
+const HEALTH_CHECK_METHOD: &str = "eth_blockNumber";
+
+/// Dispatches JSON-RPC requests to the rollup rpc urls.
+///
+/// Requests go to the rollup rpc url picked by the [`RollupSelection`] and
+/// fail over to the others on transport failures (connection errors, timeouts
+/// and 5xx responses). A JSON-RPC error returned by the rollup is a definitive answer
+/// and is never retried.
+///
+/// Rollup rpc urls that keep failing are ejected until they recover, and those
+/// trailing the highest known block by more than the maximum block lag are
+/// skipped, so that `latest` queries are not answered from a stale node. Both
+/// are only used when no better rollup rpc url is left.
+pub struct RollupClient {
+    inner: Arc<RollupClientInner>,
+}
+
+struct RollupClientInner {
+    rollup_id: String,
+    upstream_list: Vec<RollupUpstream>,
+    http_client: reqwest::Client,
+    selection: RollupSelection,
+    health_policy: HealthPolicy,
+    max_block_lag: u64,
+    health: Mutex<HashMap<String, RollupHealth>>,
+}
+
+impl Clone for RollupClient {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RollupUpstream {
+    pub rpc_url: String,
+    /// The share of requests relative to the other rollup rpc urls under
+    /// [`RollupSelection::WeightedRoundRobin`].
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RollupHealth {
+    #[serde(flatten)]
+    pub upstream: UpstreamHealth,
+    pub weight: u32,
+    pub block_number: Option<u64>,
+    /// The running weight of the smooth weighted round robin.
+    #[serde(skip)]
+    current_weight: i64,
+}
+
+impl RollupHealth {
+    fn new(upstream: &RollupUpstream) -> Self {
+        Self {
+            upstream: UpstreamHealth::new(upstream.rpc_url.clone()),
+            weight: upstream.weight,
+            block_number: None,
+            current_weight: 0,
+        }
+    }
+}
+
+impl RollupClient {
+    pub fn new(
+        rollup_id: String,
+        upstream_list: Vec<RollupUpstream>,
+        selection: RollupSelection,
+        health_policy: HealthPolicy,
+        max_block_lag: u64,
+        attempt_timeout: Duration,
+    ) -> Result<Self, RollupClientError> {
+        // A hanging rollup rpc url must fail the attempt so that it is failed
+        // over and eventually ejected.
+        let http_client = reqwest::Client::builder()
+            .connect_timeout(attempt_timeout)
+            .timeout(attempt_timeout)
+            .build()
+            .map_err(RollupClientError::BuildClient)?;
+
+        let health = upstream_list
+            .iter()
+            .map(|upstream| (upstream.rpc_url.clone(), RollupHealth::new(upstream)))
+            .collect();
+
+        let inner = RollupClientInner {
+            rollup_id,
+            upstream_list,
+            http_client,
+            selection,
+            health_policy,
+            max_block_lag,
+            health: Mutex::new(health),
+        };
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub async fn request<P, R>(&self, method: &str, parameter: P) -> Result<R, RollupClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            method,
+            params: serde_json::to_value(parameter)
+                .map_err(RollupClientError::SerializeParameter)?,
+            id: 1,
+        };
+
+        let started_at = Instant::now();
+
+        let response = self
+            .send_with_failover(&request)
+            .await
+            .and_then(|(rpc_url, response)| parse_response(&rpc_url, response));
+
+        metrics().observe_rollup_request(
+            &self.inner.rollup_id,
+            method,
+            response.is_ok(),
+            started_at.elapsed(),
+        );
+
+        response
+    }
+
+    /// Sends `request_list` as a single batch and returns the responses in the
+    /// order of the rollup, which callers match up by id.
+    pub async fn batch_request(
+        &self,
+        request_list: &[Value],
+    ) -> Result<Vec<Value>, RollupClientError> {
+        let started_at = Instant::now();
+
+        let response =
+            self.send_with_failover(request_list)
+                .await
+                .and_then(|(rpc_url, response)| match response {
+                    Value::Array(response_list) => Ok(response_list),
+                    // A rollup node answers a batch it cannot handle with a single
+                    // error response instead of a list.
+                    response => Err(parse_response::<Value>(&rpc_url, response).err().unwrap_or(
+                        RollupClientError::InvalidResponse {
+                            rpc_url,
+                            message: "expected a batch response".to_owned(),
+                        },
+                    )),
+                });
+
+        metrics().observe_rollup_request(
+            &self.inner.rollup_id,
+            BATCH_METHOD,
+            response.is_ok(),
+            started_at.elapsed(),
+        );
+
+        response
+    }
+
+    /// Returns the health of every rollup rpc url in the list.
+    pub fn pool_status(&self) -> Vec<RollupHealth> {
+        let health = self.inner.health.lock().unwrap();
+
+        self.inner
+            .upstream_list
+            .iter()
+            .filter_map(|upstream| health.get(&upstream.rpc_url).cloned())
+            .collect()
+    }
+
+    /// Probes every rollup rpc url once and updates its health and block
+    /// number.
+    pub async fn check_health(&self) {
+        let mut probes = tokio::task::JoinSet::new();
+
+        for upstream in self.inner.upstream_list.iter().cloned() {
+            let rollup_client = self.clone();
+
+            probes.spawn(async move {
+                let request = JsonRpcRequest {
+                    jsonrpc: "2.0",
+                    method: HEALTH_CHECK_METHOD,
+                    params: Value::Array(Vec::new()),
+                    id: 1,
+                };
+                let started_at = Instant::now();
+
+                let block_number = rollup_client
+                    .send(&upstream.rpc_url, &request)
+                    .await
+                    .and_then(|response| parse_response::<String>(&upstream.rpc_url, response))
+                    .and_then(|block_number| {
+                        u64::from_str_radix(block_number.trim_start_matches("0x"), 16).map_err(
+                            |error| RollupClientError::InvalidResponse {
+                                rpc_url: upstream.rpc_url.clone(),
+                                message: error.to_string(),
+                            },
+                        )
+                    });
+
+                match block_number {
+                    Ok(block_number) => {
+                        rollup_client.record_success(&upstream.rpc_url, started_at.elapsed());
+                        rollup_client.record_block_number(&upstream.rpc_url, block_number);
+                    }
+                    Err(error) => rollup_client.record_failure(&upstream.rpc_url, &error),
+                }
+            });
+        }
+
+        while probes.join_next().await.is_some() {}
+    }
+
+    /// Sends `request` to the candidates in turn until one of them answers.
+    async fn send_with_failover<T>(&self, request: &T) -> Result<(String, Value), RollupClientError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut last_error = RollupClientError::EmptyRpcUrlList;
+
+        for rpc_url in self.candidates() {
+            let started_at = Instant::now();
+
+            match self.send(&rpc_url, request).await {
+                Ok(response) => {
+                    self.record_success(&rpc_url, started_at.elapsed());
+                    return Ok((rpc_url, response));
+                }
+                Err(error) => {
+                    tracing::warn!("Rollup request to {} failed: {:?}", rpc_url, error);
+                    self.record_failure(&rpc_url, &error);
+                    last_error = error;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Returns the rollup rpc urls in the order they should be tried.
+    ///
+    /// Healthy rollup rpc urls close to the highest block come first in the
+    /// order of the selection, then lagging ones, then ejected ones.
+    fn candidates(&self) -> Vec<String> {
+        let mut health_map = self.inner.health.lock().unwrap();
+
+        let highest_block_number = health_map
+            .values()
+            .filter(|health| health.upstream.is_healthy)
+            .filter_map(|health| health.block_number)
+            .max();
+        let is_lagging = |health: &RollupHealth| match (health.block_number, highest_block_number) {
+            (Some(block_number), Some(highest_block_number)) => {
+                block_number.saturating_add(self.inner.max_block_lag) < highest_block_number
+            }
+            _ => false,
+        };
+
+        let (mut preferred, mut fallback): (Vec<&mut RollupHealth>, Vec<&mut RollupHealth>) =
+            health_map
+                .values_mut()
+                .partition(|health| health.upstream.is_healthy && !is_lagging(health));
+
+        match self.inner.selection {
+            RollupSelection::WeightedRoundRobin => {
+                // Smooth weighted round robin: the candidate with the highest
+                // running weight goes first and pays back the total weight.
+                let total_weight: i64 = preferred.iter().map(|health| health.weight as i64).sum();
+                preferred
+                    .iter_mut()
+                    .for_each(|health| health.current_weight += health.weight as i64);
+
+                let selected = preferred
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, health)| health.current_weight)
+                    .map(|(index, _)| index);
+
+                if let Some(index) = selected {
+                    preferred[index].current_weight -= total_weight;
+
+                    let selected = preferred.remove(index);
+                    preferred.sort_by_key(|health| std::cmp::Reverse(health.current_weight));
+                    preferred.insert(0, selected);
+                }
+            }
+            RollupSelection::Latency => preferred.sort_by(|a, b| {
+                // Rollup rpc urls without a measurement yet are tried first.
+                let a = a.upstream.average_latency_ms.unwrap_or_default();
+                let b = b.upstream.average_latency_ms.unwrap_or_default();
+                a.total_cmp(&b)
+            }),
+        }
+
+        fallback.sort_by_key(|health| (!health.upstream.is_healthy, is_lagging(health)));
+
+        preferred
+            .into_iter()
+            .chain(fallback)
+            .map(|health| health.upstream.rpc_url.clone())
+            .collect()
+    }
+
+    fn record_success(&self, rpc_url: &str, latency: Duration) {
+        let mut health_map = self.inner.health.lock().unwrap();
+        let Some(health) = health_map.get_mut(rpc_url) else {
+            return;
+        };
+
+        if health
+            .upstream
+            .record_success(latency, &self.inner.health_policy)
+        {
+            tracing::info!("Re-admitted rollup rpc url: {}", rpc_url);
+        }
+    }
+
+    fn record_failure(&self, rpc_url: &str, error: &RollupClientError) {
+        let mut health_map = self.inner.health.lock().unwrap();
+        let Some(health) = health_map.get_mut(rpc_url) else {
+            return;
+        };
+
+        if health
+            .upstream
+            .record_failure(error.to_string(), &self.inner.health_policy)
+        {
+            tracing::warn!(
+                "Ejected unhealthy rollup rpc url: {} ({:?})",
+                rpc_url,
+                error
+            );
+        }
+    }
+
+    fn record_block_number(&self, rpc_url: &str, block_number: u64) {
+        if let Some(health) = self.inner.health.lock().unwrap().get_mut(rpc_url) {
+            health.block_number = Some(block_number);
+        }
+    }
+
+    /// Returns the JSON-RPC response of a rollup rpc url, failing only if it
+    /// could not be reached.
+    async fn send<T>(&self, rpc_url: &str, request: &T) -> Result<Value, RollupClientError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let response = self
+            .inner
+            .http_client
+            .post(rpc_url)
+            .json(request)
+            .send()
+            .await
+            .map_err(|error| RollupClientError::Transport {
+                rpc_url: rpc_url.to_owned(),
+                error,
+            })?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(RollupClientError::ServerError {
+                rpc_url: rpc_url.to_owned(),
+                status: status.as_u16(),
+            });
+        }
+
+        response.json().await.map_err(|error| {
+            if error.is_timeout() {
+                RollupClientError::Transport {
+                    rpc_url: rpc_url.to_owned(),
+                    error,
+                }
+            } else {
+                RollupClientError::InvalidResponse {
+                    rpc_url: rpc_url.to_owned(),
+                    message: error.to_string(),
+                }
+            }
+        })
+    }
+}
+
+fn parse_response<R>(rpc_url: &str, response: Value) -> Result<R, RollupClientError>
+where
+    R: DeserializeOwned,
+{
+    let response: JsonRpcResponse =
+        serde_json::from_value(response).map_err(|error| RollupClientError::InvalidResponse {
+            rpc_url: rpc_url.to_owned(),
+            message: error.to_string(),
+        })?;
+
+    if let Some(error) = response.error {
+        return Err(RollupClientError::Rejected {
+            rpc_url: rpc_url.to_owned(),
+            error,
+        });
+    }
+
+    serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(|error| {
+        RollupClientError::InvalidResponse {
+            rpc_url: rpc_url.to_owned(),
+            message: error.to_string(),
+        }
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: Value,
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    result: Option<Value>,
+    error: Option<JsonRpcErrorObject>,
+}
+
+#[derive(Debug)]
+pub enum RollupClientError {
+    BuildClient(reqwest::Error),
+    EmptyRpcUrlList,
+    SerializeParameter(serde_json::Error),
+    Transport {
+        rpc_url: String,
+        error: reqwest::Error,
+    },
+    ServerError {
+        rpc_url: String,
+        status: u16,
+    },
+    InvalidResponse {
+        rpc_url: String,
+        message: String,
+    },
+    /// The rollup answered with a JSON-RPC error.
+    Rejected {
+        rpc_url: String,
+        error: JsonRpcErrorObject,
+    },
+}
+
+impl std::fmt::Display for RollupClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for RollupClientError {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const A: &str = "http://127.0.0.1:8545";
+    const B: &str = "http://127.0.0.1:8546";
+    const C: &str = "http://127.0.0.1:8547";
+
+    fn rollup_client(selection: RollupSelection, weight_list: [u32; 3]) -> RollupClient {
+        RollupClient::new(
+            "0".into(),
+            [A, B, C]
+                .into_iter()
+                .zip(weight_list)
+                .map(|(rpc_url, weight)| RollupUpstream {
+                    rpc_url: rpc_url.to_owned(),
+                    weight,
+                })
+                .collect(),
+            selection,
+            HealthPolicy {
+                unhealthy_threshold: 3,
+                healthy_threshold: 2,
+            },
+            5,
+            Duration::from_secs(1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn spreads_requests_by_weight() {
+        let rollup_client = rollup_client(RollupSelection::WeightedRoundRobin, [5, 1, 1]);
+        let mut selected_count: HashMap<String, usize> = HashMap::new();
+
+        for round in 0..14 {
+            let candidates = rollup_client.candidates();
+            assert_eq!(candidates.len(), 3);
+
+            // The heaviest rollup rpc url opens every cycle of the total weight.
+            if round % 7 == 0 {
+                assert_eq!(candidates[0], A);
+            }
+
+            *selected_count.entry(candidates[0].clone()).or_default() += 1;
+        }
+
+        assert_eq!(selected_count[A], 10);
+        assert_eq!(selected_count[B], 2);
+        assert_eq!(selected_count[C], 2);
+    }
+
+    #[test]
+    fn interleaves_lighter_rollup_rpc_urls() {
+        let rollup_client = rollup_client(RollupSelection::WeightedRoundRobin, [5, 1, 1]);
+
+        let selected_list: Vec<String> = (0..7)
+            .map(|_| rollup_client.candidates().remove(0))
+            .collect();
+
+        // Smooth weighted round robin never sends more than two requests in a
+        // row to `A` within a cycle of weights 5, 1 and 1.
+        assert_eq!(&selected_list[..2], [A, A]);
+        assert_ne!(selected_list[2], A);
+        assert_eq!(selected_list[3], A);
+        assert_ne!(selected_list[4], A);
+        assert_eq!(&selected_list[5..], [A, A]);
+    }
+
+    #[test]
+    fn prefers_the_lowest_latency() {
+        let rollup_client = rollup_client(RollupSelection::Latency, [1, 1, 1]);
+
+        rollup_client.record_success(A, Duration::from_millis(50));
+        rollup_client.record_success(B, Duration::from_millis(10));
+        rollup_client.record_success(C, Duration::from_millis(30));
+
+        assert_eq!(rollup_client.candidates(), [B, C, A]);
+    }
+
+    #[test]
+    fn ejects_and_readmits_rollup_rpc_urls() {
+        let rollup_client = rollup_client(RollupSelection::Latency, [1, 1, 1]);
+
+        rollup_client.record_success(A, Duration::from_millis(50));
+        rollup_client.record_success(B, Duration::from_millis(10));
+        rollup_client.record_success(C, Duration::from_millis(30));
+
+        for _ in 0..3 {
+            rollup_client.record_failure(B, &RollupClientError::EmptyRpcUrlList);
+        }
+        assert_eq!(rollup_client.candidates(), [C, A, B]);
+
+        rollup_client.record_success(B, Duration::from_millis(10));
+        assert_eq!(rollup_client.candidates(), [C, A, B]);
+
+        rollup_client.record_success(B, Duration::from_millis(10));
+        assert_eq!(rollup_client.candidates(), [B, C, A]);
+    }
+
+    #[test]
+    fn skips_lagging_rollup_rpc_urls() {
+        let rollup_client = rollup_client(RollupSelection::Latency, [1, 1, 1]);
+
+        rollup_client.record_success(A, Duration::from_millis(50));
+        rollup_client.record_success(B, Duration::from_millis(10));
+        rollup_client.record_success(C, Duration::from_millis(30));
+        rollup_client.record_block_number(A, 100);
+        rollup_client.record_block_number(B, 90);
+        rollup_client.record_block_number(C, 96);
+
+        assert_eq!(rollup_client.candidates(), [C, A, B]);
+    }
+}
@@ -0,0 +1,562 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, thread_rng};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::upstream_health::{HealthPolicy, UpstreamHealth};
+use crate::metrics::metrics;
+
+/// Dispatches JSON-RPC requests to the sequencers of a cluster.
+///
+/// Transport failures (connection errors, timeouts and 5xx responses) are
+/// retried against the other sequencers in the list, while a JSON-RPC error
+/// returned by a sequencer is a definitive rejection and is never retried.
+///
+/// Sequencers that keep failing are ejected from the selection pool until they
+/// recover, based on both request outcomes and periodic health checks.
+pub struct SequencerClient {
+    inner: Arc<SequencerClientInner>,
+}
+
+struct SequencerClientInner {
+    rollup_id: String,
+    rpc_url_list: Vec<String>,
+    http_client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    health_policy: HealthPolicy,
+    health: Mutex<HashMap<String, UpstreamHealth>>,
+}
+
+impl Clone for SequencerClient {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first one.
+    pub max_attempts: usize,
+    pub attempt_timeout: Duration,
+    /// The delay before the first retry, doubled on every following retry.
+    pub backoff: Duration,
+}
+
+/// The health of a sequencer as reported by `admin_getSequencerPool`.
+pub type SequencerHealth = UpstreamHealth;
+
+impl SequencerClient {
+    pub fn new(
+        rollup_id: String,
+        rpc_url_list: Vec<String>,
+        retry_policy: RetryPolicy,
+        health_policy: HealthPolicy,
+    ) -> Result<Self, SequencerClientError> {
+        let http_client = reqwest::Client::builder()
+            .build()
+            .map_err(SequencerClientError::BuildClient)?;
+
+        let health = rpc_url_list
+            .iter()
+            .map(|rpc_url| (rpc_url.clone(), UpstreamHealth::new(rpc_url.clone())))
+            .collect();
+
+        let inner = SequencerClientInner {
+            rollup_id,
+            rpc_url_list,
+            http_client,
+            retry_policy,
+            health_policy,
+            health: Mutex::new(health),
+        };
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    pub fn rpc_url_list(&self) -> &Vec<String> {
+        &self.inner.rpc_url_list
+    }
+
+    pub async fn request<P, R>(&self, method: &str, parameter: P) -> Result<R, SequencerClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let (_, response) = self.request_with_rpc_url(method, parameter).await?;
+
+        Ok(response)
+    }
+
+    /// Same as [`Self::request`], but also returns the rpc url of the sequencer
+    /// that answered.
+    pub async fn request_with_rpc_url<P, R>(
+        &self,
+        method: &str,
+        parameter: P,
+    ) -> Result<(String, R), SequencerClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let parameter =
+            serde_json::to_value(parameter).map_err(SequencerClientError::SerializeParameter)?;
+
+        let candidates = self.candidates();
+        if candidates.is_empty() {
+            return Err(SequencerClientError::EmptyRpcUrlList);
+        }
+
+        let retry_policy = &self.inner.retry_policy;
+        let max_attempts = retry_policy.max_attempts.max(1);
+
+        let mut attempt = 0;
+        loop {
+            let rpc_url = candidates[attempt % candidates.len()].clone();
+
+            let started_at = Instant::now();
+            let response = self.send(&rpc_url, method, &parameter).await;
+
+            match &response {
+                Ok(_) => {
+                    metrics().observe_sequencer_request(&self.inner.rollup_id, &rpc_url, "success");
+                    self.record_success(&rpc_url, started_at.elapsed())
+                }
+                Err(SequencerClientError::Rejected { .. }) => {
+                    metrics().observe_sequencer_request(
+                        &self.inner.rollup_id,
+                        &rpc_url,
+                        "rejected",
+                    );
+                    self.record_success(&rpc_url, started_at.elapsed())
+                }
+                Err(error) => {
+                    metrics().observe_sequencer_request(&self.inner.rollup_id, &rpc_url, "failure");
+                    if error.is_retryable() {
+                        self.record_failure(&rpc_url, error);
+                    }
+                }
+            }
+
+            match response {
+                Ok(response) => return Ok((rpc_url, response)),
+                Err(error) if attempt + 1 < max_attempts && error.is_retryable() => {
+                    tracing::warn!(
+                        "Sequencer request failed (attempt {}/{}): {:?}",
+                        attempt + 1,
+                        max_attempts,
+                        error
+                    );
+                }
+                Err(error) => return Err(error),
+            }
+
+            tokio::time::sleep(
+                retry_policy
+                    .backoff
+                    .saturating_mul(2u32.saturating_pow(attempt as u32)),
+            )
+            .await;
+
+            attempt += 1;
+        }
+    }
+
+    /// Returns the health of every sequencer in the list.
+    pub fn pool_status(&self) -> Vec<SequencerHealth> {
+        let health = self.inner.health.lock().unwrap();
+
+        self.inner
+            .rpc_url_list
+            .iter()
+            .filter_map(|rpc_url| health.get(rpc_url).cloned())
+            .collect()
+    }
+
+    /// Probes every sequencer once and updates its health.
+    ///
+    /// Only a successful [`GetRawTransactionList`] result counts, so that a
+    /// node which is up but does not serve the rollup is ejected as well.
+    pub async fn check_health(&self) {
+        let mut probes = tokio::task::JoinSet::new();
+
+        let parameter = GetRawTransactionList {
+            rollup_id: self.inner.rollup_id.clone(),
+            rollup_block_height: 0,
+        };
+        let parameter = match serde_json::to_value(parameter) {
+            Ok(parameter) => parameter,
+            Err(error) => {
+                tracing::error!("Failed to serialize the sequencer probe: {:?}", error);
+                return;
+            }
+        };
+
+        for rpc_url in self.inner.rpc_url_list.iter().cloned() {
+            let sequencer_client = self.clone();
+            let parameter = parameter.clone();
+
+            probes.spawn(async move {
+                let started_at = Instant::now();
+
+                match sequencer_client
+                    .send::<Value>(&rpc_url, GetRawTransactionList::METHOD_NAME, &parameter)
+                    .await
+                {
+                    Ok(_) => sequencer_client.record_success(&rpc_url, started_at.elapsed()),
+                    Err(error) => sequencer_client.record_failure(&rpc_url, &error),
+                }
+            });
+        }
+
+        while probes.join_next().await.is_some() {}
+    }
+
+    /// Returns the sequencer RPC URLs in the order they should be tried.
+    ///
+    /// Ejected sequencers are only used when no healthy sequencer is left.
+    fn candidates(&self) -> Vec<String> {
+        let health = self.inner.health.lock().unwrap();
+        let is_healthy = |rpc_url: &String| {
+            health
+                .get(rpc_url)
+                .map(|health| health.is_healthy)
+                .unwrap_or(true)
+        };
+
+        let mut candidates: Vec<String> = self
+            .inner
+            .rpc_url_list
+            .iter()
+            .filter(|rpc_url| is_healthy(rpc_url))
+            .cloned()
+            .collect();
+
+        if candidates.is_empty() {
+            candidates = self.inner.rpc_url_list.clone();
+        }
+        candidates.shuffle(&mut thread_rng());
+
+        candidates
+    }
+
+    fn record_success(&self, rpc_url: &str, latency: Duration) {
+        let mut health_map = self.inner.health.lock().unwrap();
+        let Some(health) = health_map.get_mut(rpc_url) else {
+            return;
+        };
+
+        if health.record_success(latency, &self.inner.health_policy) {
+            tracing::info!("Re-admitted sequencer: {}", rpc_url);
+        }
+    }
+
+    fn record_failure(&self, rpc_url: &str, error: &SequencerClientError) {
+        let mut health_map = self.inner.health.lock().unwrap();
+        let Some(health) = health_map.get_mut(rpc_url) else {
+            return;
+        };
+
+        if health.record_failure(error.to_string(), &self.inner.health_policy) {
+            tracing::warn!("Ejected unhealthy sequencer: {} ({:?})", rpc_url, error);
+        }
+    }
+
+    async fn send<R>(
+        &self,
+        rpc_url: &str,
+        method: &str,
+        parameter: &Value,
+    ) -> Result<R, SequencerClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            method,
+            params: parameter,
+            id: 1,
+        };
+
+        let response = self
+            .inner
+            .http_client
+            .post(rpc_url)
+            .timeout(self.inner.retry_policy.attempt_timeout)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|error| SequencerClientError::Transport {
+                rpc_url: rpc_url.to_owned(),
+                error,
+            })?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(SequencerClientError::ServerError {
+                rpc_url: rpc_url.to_owned(),
+                status: status.as_u16(),
+            });
+        }
+
+        let response: JsonRpcResponse =
+            response
+                .json()
+                .await
+                .map_err(|error| SequencerClientError::InvalidResponse {
+                    rpc_url: rpc_url.to_owned(),
+                    message: error.to_string(),
+                })?;
+
+        if let Some(error) = response.error {
+            return Err(SequencerClientError::Rejected {
+                rpc_url: rpc_url.to_owned(),
+                error,
+            });
+        }
+
+        serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(|error| {
+            SequencerClientError::InvalidResponse {
+                rpc_url: rpc_url.to_owned(),
+                message: error.to_string(),
+            }
+        })
+    }
+}
+
+/// Sent to probe a sequencer, a read-only method of its external RPC.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct GetRawTransactionList {
+    rollup_id: String,
+    rollup_block_height: u64,
+}
+
+impl GetRawTransactionList {
+    const METHOD_NAME: &'static str = "get_raw_transaction_list";
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: &'a Value,
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    result: Option<Value>,
+    error: Option<JsonRpcErrorObject>,
+}
+
+/// The error object of a JSON-RPC response returned by a sequencer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonRpcErrorObject {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug)]
+pub enum SequencerClientError {
+    BuildClient(reqwest::Error),
+    EmptyRpcUrlList,
+    SerializeParameter(serde_json::Error),
+    Transport {
+        rpc_url: String,
+        error: reqwest::Error,
+    },
+    ServerError {
+        rpc_url: String,
+        status: u16,
+    },
+    InvalidResponse {
+        rpc_url: String,
+        message: String,
+    },
+    /// The sequencer answered with a JSON-RPC error.
+    Rejected {
+        rpc_url: String,
+        error: JsonRpcErrorObject,
+    },
+}
+
+impl SequencerClientError {
+    /// Returns `true` if the request may succeed on another sequencer.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Transport { .. } | Self::ServerError { .. })
+    }
+}
+
+impl std::fmt::Display for SequencerClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for SequencerClientError {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{http::StatusCode, routing::post, Json, Router};
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    enum Answer {
+        Result,
+        Error,
+        ServerError,
+    }
+
+    /// A local sequencer answering every request with its current answer.
+    struct StubSequencer {
+        rpc_url: String,
+        answer: Arc<Mutex<Answer>>,
+        request_count: Arc<AtomicUsize>,
+    }
+
+    impl StubSequencer {
+        async fn start(answer: Answer) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+            let answer = Arc::new(Mutex::new(answer));
+            let request_count = Arc::new(AtomicUsize::new(0));
+
+            let router = Router::new().route(
+                "/",
+                post({
+                    let answer = answer.clone();
+                    let request_count = request_count.clone();
+
+                    move || async move {
+                        request_count.fetch_add(1, Ordering::SeqCst);
+
+                        let answer = *answer.lock().unwrap();
+                        match answer {
+                            Answer::Result => (
+                                StatusCode::OK,
+                                Json(json!({ "jsonrpc": "2.0", "result": [], "id": 1 })),
+                            ),
+                            Answer::Error => (
+                                StatusCode::OK,
+                                Json(json!({
+                                    "jsonrpc": "2.0",
+                                    "error": { "code": -32000, "message": "rejected" },
+                                    "id": 1,
+                                })),
+                            ),
+                            Answer::ServerError => (StatusCode::BAD_GATEWAY, Json(json!({}))),
+                        }
+                    }
+                }),
+            );
+            tokio::spawn(async move { axum::serve(listener, router).await });
+
+            Self {
+                rpc_url,
+                answer,
+                request_count,
+            }
+        }
+
+        fn answer(&self, answer: Answer) {
+            *self.answer.lock().unwrap() = answer;
+        }
+
+        fn request_count(&self) -> usize {
+            self.request_count.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Returns an rpc url nothing listens on.
+    async fn unreachable_rpc_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    fn sequencer_client(rpc_url_list: Vec<String>, max_attempts: usize) -> SequencerClient {
+        SequencerClient::new(
+            "0".into(),
+            rpc_url_list,
+            RetryPolicy {
+                max_attempts,
+                attempt_timeout: Duration::from_secs(1),
+                backoff: Duration::ZERO,
+            },
+            HealthPolicy {
+                unhealthy_threshold: 2,
+                healthy_threshold: 2,
+            },
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retries_transport_and_server_errors() {
+        let failing_sequencer = StubSequencer::start(Answer::ServerError).await;
+        let sequencer = StubSequencer::start(Answer::Result).await;
+        let sequencer_client = sequencer_client(
+            vec![
+                unreachable_rpc_url().await,
+                failing_sequencer.rpc_url.clone(),
+                sequencer.rpc_url.clone(),
+            ],
+            3,
+        );
+
+        // Every attempt goes to another sequencer, so the third one at the
+        // latest reaches the answering sequencer.
+        let (rpc_url, _) = sequencer_client
+            .request_with_rpc_url::<_, Value>("get_raw_transaction_list", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(rpc_url, sequencer.rpc_url);
+        assert_eq!(sequencer.request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_rejections() {
+        let sequencer = StubSequencer::start(Answer::Error).await;
+        let sequencer_client = sequencer_client(vec![sequencer.rpc_url.clone()], 3);
+
+        let error = sequencer_client
+            .request::<_, Value>("send_raw_transaction", json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SequencerClientError::Rejected { .. }));
+        assert_eq!(sequencer.request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn ejects_and_readmits_sequencers_on_health_checks() {
+        let sequencer = StubSequencer::start(Answer::Error).await;
+        let sequencer_client = sequencer_client(vec![sequencer.rpc_url.clone()], 1);
+
+        // A sequencer that is up but refuses the probe is ejected as well.
+        sequencer_client.check_health().await;
+        assert!(sequencer_client.pool_status()[0].is_healthy);
+        sequencer_client.check_health().await;
+        assert!(!sequencer_client.pool_status()[0].is_healthy);
+
+        sequencer.answer(Answer::Result);
+        sequencer_client.check_health().await;
+        assert!(!sequencer_client.pool_status()[0].is_healthy);
+        sequencer_client.check_health().await;
+        assert!(sequencer_client.pool_status()[0].is_healthy);
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug)]
+pub struct HealthPolicy {
+    /// Consecutive failures after which an upstream is ejected.
+    pub unhealthy_threshold: u32,
+    /// Consecutive successes after which an ejected upstream is re-admitted.
+    pub healthy_threshold: u32,
+}
+
+/// Request outcomes and latency of an upstream rpc url, which decide whether it
+/// is ejected from the selection pool.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UpstreamHealth {
+    pub rpc_url: String,
+    pub is_healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub average_latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub consecutive_successes: u32,
+    pub last_error: Option<String>,
+    pub last_checked_at: Option<u64>,
+}
+
+impl UpstreamHealth {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc_url,
+            is_healthy: true,
+            latency_ms: None,
+            average_latency_ms: None,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            last_error: None,
+            last_checked_at: None,
+        }
+    }
+
+    /// Records a successful request and returns `true` if it re-admitted the
+    /// upstream.
+    pub fn record_success(&mut self, latency: Duration, health_policy: &HealthPolicy) -> bool {
+        let latency_ms = latency.as_millis() as u64;
+        self.latency_ms = Some(latency_ms);
+        self.average_latency_ms = Some(match self.average_latency_ms {
+            Some(average_latency_ms) => average_latency_ms * 0.8 + latency_ms as f64 * 0.2,
+            None => latency_ms as f64,
+        });
+        self.consecutive_failures = 0;
+        self.consecutive_successes = self.consecutive_successes.saturating_add(1);
+        self.last_checked_at = Some(now());
+
+        if !self.is_healthy && self.consecutive_successes >= health_policy.healthy_threshold {
+            self.is_healthy = true;
+            return true;
+        }
+
+        false
+    }
+
+    /// Records a failed request and returns `true` if it ejected the upstream.
+    pub fn record_failure(&mut self, error: String, health_policy: &HealthPolicy) -> bool {
+        self.consecutive_successes = 0;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_error = Some(error);
+        self.last_checked_at = Some(now());
+
+        if self.is_healthy && self.consecutive_failures >= health_policy.unhealthy_threshold {
+            self.is_healthy = false;
+            return true;
+        }
+
+        false
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEALTH_POLICY: HealthPolicy = HealthPolicy {
+        unhealthy_threshold: 3,
+        healthy_threshold: 2,
+    };
+
+    #[test]
+    fn ejects_after_consecutive_failures() {
+        let mut health = UpstreamHealth::new("http://127.0.0.1:8545".into());
+
+        assert!(!health.record_failure("error".into(), &HEALTH_POLICY));
+        assert!(!health.record_failure("error".into(), &HEALTH_POLICY));
+        // A success in between resets the count.
+        assert!(!health.record_success(Duration::from_millis(10), &HEALTH_POLICY));
+        assert!(!health.record_failure("error".into(), &HEALTH_POLICY));
+        assert!(!health.record_failure("error".into(), &HEALTH_POLICY));
+        assert!(health.is_healthy);
+
+        assert!(health.record_failure("error".into(), &HEALTH_POLICY));
+        assert!(!health.is_healthy);
+        assert_eq!(health.last_error.as_deref(), Some("error"));
+
+        // Already ejected.
+        assert!(!health.record_failure("error".into(), &HEALTH_POLICY));
+    }
+
+    #[test]
+    fn readmits_after_consecutive_successes() {
+        let mut health = UpstreamHealth::new("http://127.0.0.1:8545".into());
+        for _ in 0..3 {
+            health.record_failure("error".into(), &HEALTH_POLICY);
+        }
+
+        assert!(!health.record_success(Duration::from_millis(10), &HEALTH_POLICY));
+        assert!(!health.is_healthy);
+        assert!(health.record_success(Duration::from_millis(10), &HEALTH_POLICY));
+        assert!(health.is_healthy);
+    }
+
+    #[test]
+    fn averages_latency() {
+        let mut health = UpstreamHealth::new("http://127.0.0.1:8545".into());
+
+        health.record_success(Duration::from_millis(100), &HEALTH_POLICY);
+        assert_eq!(health.average_latency_ms, Some(100.0));
+
+        health.record_success(Duration::from_millis(200), &HEALTH_POLICY);
+        assert_eq!(health.latency_ms, Some(200));
+        assert!((health.average_latency_ms.unwrap() - 120.0).abs() < 1e-9);
+    }
+}
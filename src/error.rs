@@ -1,3 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::client::{rollup::RollupClientError, sequencer::SequencerClientError};
+
+/// EIP-1474 JSON-RPC error codes.
+pub mod error_code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const INVALID_INPUT: i64 = -32000;
+    pub const RESOURCE_NOT_FOUND: i64 = -32001;
+    pub const RESOURCE_UNAVAILABLE: i64 = -32002;
+    pub const TRANSACTION_REJECTED: i64 = -32003;
+    pub const METHOD_NOT_SUPPORTED: i64 = -32004;
+    pub const LIMIT_EXCEEDED: i64 = -32005;
+}
+
 #[derive(Debug)]
 pub enum Error {
     Syscall(std::io::Error),
@@ -8,15 +28,25 @@ pub enum Error {
     InvalidSequencerPort,
     InvalidSecureRpcPort,
 
+    MethodNotFound(String),
+    InvalidParams(String),
     EmptyRawTransaction,
+    EmptyTransactionHash,
     EmptySequencerRpcUrl,
+    Sequencer(SequencerClientError),
+    Rollup(RollupClientError),
+    InvalidOrderCommitment(String),
 
-    DistributedKeyGenerationClient(radius_sdk::json_rpc::client::RpcClientError),
+    DistributedKeyGenerationClient(
+        crate::client::distributed_key_generation::DistributedKeyGenerationClientError,
+    ),
     LoadConfigOption,
     ParseTomlString,
     RemoveConfigDirectory,
     CreateConfigDirectory,
     CreateConfigFile,
+    Journal(std::io::Error),
+    Json(serde_json::Error),
 
     // Context
     ContextUpdateFail,
@@ -25,19 +55,366 @@ pub enum Error {
     NoneType,
 
     FailedToGetSkdeParams,
+    EncryptionKeyUnavailable,
+    EncryptionKeyExpired,
 
     DecodeFailed,
+    PvdeParamsUnavailable,
     PvdeZkpInvalid,
+    TimeLockPuzzleMismatch,
     DecryptionError(skde::delay_encryption::DecryptionError),
 
     EncryptionNotEnabled,
     UnsupportedEncryptionType,
     UnsupportedDecryptionType,
     UnsupportedTransactionType,
+    EncryptedBundleUnsupported,
+    RateLimitExceeded,
+    SubscriptionLimitExceeded,
+    InvalidApiKey,
+    EmptyBatch,
+    BatchTooLarge {
+        size: usize,
+        max_size: usize,
+    },
+    MethodNotAllowed(String),
+
+    // Transaction validation
+    TransactionTooLarge {
+        size: usize,
+        max_size: usize,
+    },
+    InvalidTransactionEncoding,
+    InvalidTransactionSignature,
+    InvalidBundle,
+    ChainIdMismatch {
+        expected: u64,
+        actual: Option<u64>,
+    },
+    GasLimitTooHigh {
+        gas_limit: String,
+        max_gas_limit: u64,
+    },
+    RollupChainIdUnavailable,
+    NonceTooLow {
+        nonce: String,
+        pending_nonce: String,
+    },
+    InsufficientBalance {
+        max_cost: String,
+        balance: String,
+    },
+    AccountStateUnavailable,
+    RollupWebSocketUnavailable,
 }
 
 unsafe impl Send for Error {}
 
+impl Error {
+    /// Returns the JSON-RPC error code of the error.
+    ///
+    /// | Code     | Meaning              | Errors                                                  |
+    /// |----------|----------------------|---------------------------------------------------------|
+    /// | `-32000` | Invalid input        | Malformed raw or encrypted transactions, invalid proofs |
+    /// |          |                      | and transactions failing validation                     |
+    /// | `-32002` | Resource unavailable | DKG, encryption keys, PVDE parameters, sequencers or    |
+    /// |          |                      | the rollup down, invalid order commitments              |
+    /// | `-32003` | Transaction rejected | The sequencer rejected the transaction                  |
+    /// | `-32004` | Method not supported | Encryption disabled, unsupported encryption types,      |
+    /// |          |                      | encrypted bundles,                                      |
+    /// |          |                      | methods the API key is not allowed to call              |
+    /// | `-32005` | Limit exceeded       | The client ran out of its rate limit budget or          |
+    /// |          |                      | subscriptions                                           |
+    /// | `-32600` | Invalid request      | Unknown API key, empty or oversized batches             |
+    /// | `-32601` | Method not found     | Methods the RPC server does not serve                   |
+    /// | `-32602` | Invalid params       | Missing or malformed parameters                         |
+    /// | `-32603` | Internal error       | Configuration, system and context errors               |
+    ///
+    /// JSON-RPC errors returned by the rollup keep the code of the rollup.
+    pub fn code(&self) -> i64 {
+        match self {
+            Self::MethodNotFound(_) => error_code::METHOD_NOT_FOUND,
+
+            Self::InvalidParams(_) | Self::EmptyRawTransaction | Self::EmptyTransactionHash => {
+                error_code::INVALID_PARAMS
+            }
+
+            Self::InvalidApiKey | Self::EmptyBatch | Self::BatchTooLarge { .. } => {
+                error_code::INVALID_REQUEST
+            }
+
+            Self::DecodeFailed
+            | Self::PvdeZkpInvalid
+            | Self::TimeLockPuzzleMismatch
+            | Self::DecryptionError(_)
+            | Self::TransactionTooLarge { .. }
+            | Self::InvalidTransactionEncoding
+            | Self::InvalidTransactionSignature
+            | Self::InvalidBundle
+            | Self::ChainIdMismatch { .. }
+            | Self::GasLimitTooHigh { .. }
+            | Self::NonceTooLow { .. }
+            | Self::InsufficientBalance { .. } => error_code::INVALID_INPUT,
+
+            Self::Uninitialized
+            | Self::FetchResponse
+            | Self::EmptySequencerRpcUrl
+            | Self::DistributedKeyGenerationClient(_)
+            | Self::FailedToGetSkdeParams
+            | Self::EncryptionKeyUnavailable
+            | Self::EncryptionKeyExpired
+            | Self::PvdeParamsUnavailable
+            | Self::RollupChainIdUnavailable
+            | Self::AccountStateUnavailable
+            | Self::RollupWebSocketUnavailable
+            | Self::InvalidOrderCommitment(_) => error_code::RESOURCE_UNAVAILABLE,
+
+            Self::Sequencer(error) => match error {
+                SequencerClientError::Rejected { .. } => error_code::TRANSACTION_REJECTED,
+                SequencerClientError::BuildClient(_)
+                | SequencerClientError::SerializeParameter(_) => error_code::INTERNAL_ERROR,
+                _ => error_code::RESOURCE_UNAVAILABLE,
+            },
+
+            Self::Rollup(error) => match error {
+                RollupClientError::Rejected { error, .. } => error.code,
+                RollupClientError::BuildClient(_) | RollupClientError::SerializeParameter(_) => {
+                    error_code::INTERNAL_ERROR
+                }
+                _ => error_code::RESOURCE_UNAVAILABLE,
+            },
+
+            Self::EncryptionNotEnabled
+            | Self::UnsupportedEncryptionType
+            | Self::UnsupportedDecryptionType
+            | Self::UnsupportedTransactionType
+            | Self::EncryptedBundleUnsupported
+            | Self::MethodNotAllowed(_) => error_code::METHOD_NOT_SUPPORTED,
+
+            Self::RateLimitExceeded | Self::SubscriptionLimitExceeded => error_code::LIMIT_EXCEEDED,
+
+            Self::Syscall(_)
+            | Self::Config(_)
+            | Self::RpcServer(_)
+            | Self::InvalidSequencerPort
+            | Self::InvalidSecureRpcPort
+            | Self::LoadConfigOption
+            | Self::ParseTomlString
+            | Self::RemoveConfigDirectory
+            | Self::CreateConfigDirectory
+            | Self::CreateConfigFile
+            | Self::Journal(_)
+            | Self::Json(_)
+            | Self::ContextUpdateFail
+            | Self::KeyDoesNotExist
+            | Self::Downcast
+            | Self::NoneType => error_code::INTERNAL_ERROR,
+        }
+    }
+
+    /// Returns the name of the variant, which clients can match on.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Syscall(_) => "Syscall",
+            Self::Config(_) => "Config",
+            Self::RpcServer(_) => "RpcServer",
+            Self::Uninitialized => "Uninitialized",
+            Self::FetchResponse => "FetchResponse",
+            Self::InvalidSequencerPort => "InvalidSequencerPort",
+            Self::InvalidSecureRpcPort => "InvalidSecureRpcPort",
+            Self::MethodNotFound(_) => "MethodNotFound",
+            Self::InvalidParams(_) => "InvalidParams",
+            Self::EmptyRawTransaction => "EmptyRawTransaction",
+            Self::EmptyTransactionHash => "EmptyTransactionHash",
+            Self::EmptySequencerRpcUrl => "EmptySequencerRpcUrl",
+            Self::Sequencer(_) => "Sequencer",
+            Self::Rollup(_) => "Rollup",
+            Self::InvalidOrderCommitment(_) => "InvalidOrderCommitment",
+            Self::DistributedKeyGenerationClient(_) => "DistributedKeyGenerationClient",
+            Self::LoadConfigOption => "LoadConfigOption",
+            Self::ParseTomlString => "ParseTomlString",
+            Self::RemoveConfigDirectory => "RemoveConfigDirectory",
+            Self::CreateConfigDirectory => "CreateConfigDirectory",
+            Self::CreateConfigFile => "CreateConfigFile",
+            Self::Journal(_) => "Journal",
+            Self::Json(_) => "Json",
+            Self::ContextUpdateFail => "ContextUpdateFail",
+            Self::KeyDoesNotExist => "KeyDoesNotExist",
+            Self::Downcast => "Downcast",
+            Self::NoneType => "NoneType",
+            Self::FailedToGetSkdeParams => "FailedToGetSkdeParams",
+            Self::EncryptionKeyUnavailable => "EncryptionKeyUnavailable",
+            Self::EncryptionKeyExpired => "EncryptionKeyExpired",
+            Self::DecodeFailed => "DecodeFailed",
+            Self::PvdeParamsUnavailable => "PvdeParamsUnavailable",
+            Self::PvdeZkpInvalid => "PvdeZkpInvalid",
+            Self::TimeLockPuzzleMismatch => "TimeLockPuzzleMismatch",
+            Self::DecryptionError(_) => "DecryptionError",
+            Self::EncryptionNotEnabled => "EncryptionNotEnabled",
+            Self::UnsupportedEncryptionType => "UnsupportedEncryptionType",
+            Self::UnsupportedDecryptionType => "UnsupportedDecryptionType",
+            Self::UnsupportedTransactionType => "UnsupportedTransactionType",
+            Self::EncryptedBundleUnsupported => "EncryptedBundleUnsupported",
+            Self::RateLimitExceeded => "RateLimitExceeded",
+            Self::SubscriptionLimitExceeded => "SubscriptionLimitExceeded",
+            Self::InvalidApiKey => "InvalidApiKey",
+            Self::EmptyBatch => "EmptyBatch",
+            Self::BatchTooLarge { .. } => "BatchTooLarge",
+            Self::MethodNotAllowed(_) => "MethodNotAllowed",
+            Self::TransactionTooLarge { .. } => "TransactionTooLarge",
+            Self::InvalidTransactionEncoding => "InvalidTransactionEncoding",
+            Self::InvalidTransactionSignature => "InvalidTransactionSignature",
+            Self::InvalidBundle => "InvalidBundle",
+            Self::ChainIdMismatch { .. } => "ChainIdMismatch",
+            Self::GasLimitTooHigh { .. } => "GasLimitTooHigh",
+            Self::RollupChainIdUnavailable => "RollupChainIdUnavailable",
+            Self::NonceTooLow { .. } => "NonceTooLow",
+            Self::InsufficientBalance { .. } => "InsufficientBalance",
+            Self::AccountStateUnavailable => "AccountStateUnavailable",
+            Self::RollupWebSocketUnavailable => "RollupWebSocketUnavailable",
+        }
+    }
+
+    /// Returns the human readable message of the error.
+    pub fn message(&self) -> &'static str {
+        match self.code() {
+            error_code::METHOD_NOT_FOUND => "method not found",
+            error_code::INVALID_PARAMS => "invalid params",
+            error_code::INVALID_INPUT => match self {
+                Self::TransactionTooLarge { .. } => "transaction too large",
+                Self::InvalidTransactionEncoding => "invalid transaction encoding",
+                Self::InvalidTransactionSignature => "invalid transaction signature",
+                Self::InvalidBundle => "invalid bundle",
+                Self::TimeLockPuzzleMismatch => "time lock puzzle mismatch",
+                Self::ChainIdMismatch { .. } => "chain id mismatch",
+                Self::GasLimitTooHigh { .. } => "gas limit too high",
+                Self::NonceTooLow { .. } => "nonce too low",
+                Self::InsufficientBalance { .. } => "insufficient funds for gas * price + value",
+                _ => "invalid input",
+            },
+            error_code::RESOURCE_UNAVAILABLE => match self {
+                Self::Sequencer(_) | Self::EmptySequencerRpcUrl => "sequencer unavailable",
+                Self::InvalidOrderCommitment(_) => "invalid order commitment",
+                Self::Uninitialized | Self::FetchResponse => "resource unavailable",
+                Self::Rollup(_)
+                | Self::RollupChainIdUnavailable
+                | Self::AccountStateUnavailable
+                | Self::RollupWebSocketUnavailable => "rollup unavailable",
+                _ => "encryption unavailable",
+            },
+            error_code::TRANSACTION_REJECTED => "transaction rejected by the sequencer",
+            error_code::INVALID_REQUEST => match self {
+                Self::InvalidApiKey => "unauthorized",
+                _ => "invalid request",
+            },
+            error_code::METHOD_NOT_SUPPORTED => match self {
+                Self::MethodNotAllowed(_) => "method not allowed",
+                Self::EncryptedBundleUnsupported => "encrypted bundles are not supported",
+                _ => "method not supported",
+            },
+            error_code::LIMIT_EXCEEDED => "limit exceeded",
+            _ => "internal error",
+        }
+    }
+
+    /// Returns the structured `data` payload of the error.
+    pub fn data(&self) -> Value {
+        match self {
+            // The sequencer rpc url is internal topology and only logged.
+            Self::Sequencer(SequencerClientError::Rejected { error, .. }) => json!({
+                "kind": self.kind(),
+                "sequencer_error": error,
+            }),
+            Self::MethodNotAllowed(method) | Self::MethodNotFound(method) => json!({
+                "kind": self.kind(),
+                "method": method,
+            }),
+            Self::InvalidParams(reason) => json!({
+                "kind": self.kind(),
+                "reason": reason,
+            }),
+            Self::BatchTooLarge { size, max_size } => json!({
+                "kind": self.kind(),
+                "size": size,
+                "max_size": max_size,
+            }),
+            Self::TransactionTooLarge { size, max_size } => json!({
+                "kind": self.kind(),
+                "size": size,
+                "max_size": max_size,
+            }),
+            Self::ChainIdMismatch { expected, actual } => json!({
+                "kind": self.kind(),
+                "expected": expected,
+                "actual": actual,
+            }),
+            Self::GasLimitTooHigh {
+                gas_limit,
+                max_gas_limit,
+            } => json!({
+                "kind": self.kind(),
+                "gas_limit": gas_limit,
+                "max_gas_limit": max_gas_limit,
+            }),
+            Self::NonceTooLow {
+                nonce,
+                pending_nonce,
+            } => json!({
+                "kind": self.kind(),
+                "nonce": nonce,
+                "pending_nonce": pending_nonce,
+            }),
+            Self::InsufficientBalance { max_cost, balance } => json!({
+                "kind": self.kind(),
+                "max_cost": max_cost,
+                "balance": balance,
+            }),
+            Self::InvalidOrderCommitment(reason) => json!({
+                "kind": self.kind(),
+                "reason": reason,
+            }),
+            _ => json!({ "kind": self.kind() }),
+        }
+    }
+
+    pub fn to_rpc_error_object(&self) -> RpcErrorObject {
+        // Pass JSON-RPC errors of the rollup through as is, so that clients see
+        // revert reasons and data.
+        if let Self::Rollup(RollupClientError::Rejected { error, .. }) = self {
+            return RpcErrorObject {
+                code: error.code,
+                message: error.message.clone(),
+                data: error.data.clone(),
+            };
+        }
+
+        RpcErrorObject {
+            code: self.code(),
+            message: self.message().to_owned(),
+            data: Some(self.data()),
+        }
+    }
+}
+
+/// A JSON-RPC error object.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RpcErrorObject {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcErrorObject {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -63,3 +440,15 @@ impl From<radius_sdk::json_rpc::server::RpcServerError> for Error {
         Self::RpcServer(value)
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl From<RollupClientError> for Error {
+    fn from(value: RollupClientError) -> Self {
+        Self::Rollup(value)
+    }
+}
@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, types::config::ConfigError};
+
+/// Bearer API keys and the RPC methods each of them may call.
+///
+/// Loaded from `api_keys.toml` in the config path:
+///
+/// ```toml
+/// # Methods callable without an API key.
+/// anonymous_method_list = ["eth_*", "send_encrypted_transaction"]
+///
+/// [[api_key]]
+/// name = "operator"
+/// key = "<secret>"
+/// method_list = ["*"]
+/// ```
+///
+/// A method pattern is either `*`, a prefix ending with `*` or an exact method.
+pub struct ApiKeyList {
+    inner: Arc<ApiKeyListInner>,
+}
+
+struct ApiKeyListInner {
+    anonymous_method_list: Vec<String>,
+    api_key_list: HashMap<String, ApiKey>,
+}
+
+impl Clone for ApiKeyList {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct ApiKeyFile {
+    #[serde(default)]
+    anonymous_method_list: Vec<String>,
+    #[serde(default, rename = "api_key")]
+    api_key_list: Vec<ApiKey>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ApiKey {
+    pub name: String,
+    pub key: String,
+    pub method_list: Vec<String>,
+}
+
+impl ApiKeyList {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let api_key_string = fs::read_to_string(path).map_err(ConfigError::LoadApiKeys)?;
+        let api_key_file: ApiKeyFile =
+            toml::from_str(&api_key_string).map_err(ConfigError::ParseApiKeys)?;
+
+        Ok(Self::new(api_key_file))
+    }
+
+    fn new(api_key_file: ApiKeyFile) -> Self {
+        let inner = ApiKeyListInner {
+            anonymous_method_list: api_key_file.anonymous_method_list,
+            api_key_list: api_key_file
+                .api_key_list
+                .into_iter()
+                .map(|api_key| (api_key.key.clone(), api_key))
+                .collect(),
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Checks that the caller may call every method in `method_list` and
+    /// returns the name of its API key, or `None` for anonymous callers.
+    pub fn authorize(
+        &self,
+        key: Option<&str>,
+        method_list: &[&str],
+    ) -> Result<Option<&str>, Error> {
+        let (name, allowed_method_list) = match key {
+            Some(key) => {
+                let api_key = self
+                    .inner
+                    .api_key_list
+                    .get(key)
+                    .ok_or(Error::InvalidApiKey)?;
+
+                (Some(api_key.name.as_str()), &api_key.method_list)
+            }
+            None => (None, &self.inner.anonymous_method_list),
+        };
+
+        match method_list
+            .iter()
+            .find(|method| !matches_any(allowed_method_list, method))
+        {
+            Some(method) => Err(Error::MethodNotAllowed(method.to_string())),
+            None => Ok(name),
+        }
+    }
+}
+
+/// Returns `true` if `method` matches any of the method patterns.
+pub(super) fn matches_any(pattern_list: &[String], method: &str) -> bool {
+    pattern_list
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => pattern == method,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+    use crate::gateway::{ClientId, Quota, RateLimiter};
+
+    fn api_key_list() -> ApiKeyList {
+        let api_key_file: ApiKeyFile = toml::from_str(
+            r#"
+            anonymous_method_list = ["eth_*"]
+
+            [[api_key]]
+            name = "operator"
+            key = "operator-secret"
+            method_list = ["*"]
+
+            [[api_key]]
+            name = "wallet"
+            key = "wallet-secret"
+            method_list = ["eth_*", "send_encrypted_transaction"]
+            "#,
+        )
+        .unwrap();
+
+        ApiKeyList::new(api_key_file)
+    }
+
+    #[test]
+    fn authorizes_anonymous_callers_for_anonymous_methods_only() {
+        let api_key_list = api_key_list();
+
+        assert_eq!(api_key_list.authorize(None, &["eth_call"]).unwrap(), None);
+        assert!(matches!(
+            api_key_list.authorize(None, &["eth_call", "send_encrypted_transaction"]),
+            Err(Error::MethodNotAllowed(method)) if method == "send_encrypted_transaction"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let api_key_list = api_key_list();
+
+        assert!(matches!(
+            api_key_list.authorize(Some("guess"), &["eth_call"]),
+            Err(Error::InvalidApiKey)
+        ));
+        assert!(matches!(
+            api_key_list.authorize(Some(""), &["eth_call"]),
+            Err(Error::InvalidApiKey)
+        ));
+    }
+
+    #[test]
+    fn authorizes_valid_keys_for_their_methods() {
+        let api_key_list = api_key_list();
+
+        assert_eq!(
+            api_key_list
+                .authorize(Some("wallet-secret"), &["send_encrypted_transaction"])
+                .unwrap(),
+            Some("wallet")
+        );
+        assert!(matches!(
+            api_key_list.authorize(Some("wallet-secret"), &["admin_get_sequencer_pool"]),
+            Err(Error::MethodNotAllowed(_))
+        ));
+        assert_eq!(
+            api_key_list
+                .authorize(Some("operator-secret"), &["admin_get_sequencer_pool"])
+                .unwrap(),
+            Some("operator")
+        );
+    }
+
+    #[test]
+    fn rate_limits_valid_keys_by_their_name() {
+        let api_key_list = api_key_list();
+        let quota = Quota {
+            requests_per_second: 1,
+            burst: 1,
+        };
+        let rate_limiter = RateLimiter::new(quota, quota, true);
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let api_key_name = api_key_list
+            .authorize(Some("wallet-secret"), &["eth_call"])
+            .unwrap();
+        assert_eq!(
+            rate_limiter.client_id(ip_address, api_key_name),
+            ClientId::ApiKey("wallet".into())
+        );
+
+        // Anonymous callers are rate limited by their IP address.
+        let api_key_name = api_key_list.authorize(None, &["eth_call"]).unwrap();
+        assert_eq!(
+            rate_limiter.client_id(ip_address, api_key_name),
+            ClientId::IpAddress(ip_address)
+        );
+    }
+}
@@ -0,0 +1,727 @@
+mod auth;
+mod passthrough;
+mod rate_limiter;
+mod rollup_router;
+mod websocket;
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Instant,
+};
+
+pub use auth::{ApiKey, ApiKeyList};
+use axum::{
+    body::Bytes,
+    extract::ws::WebSocketUpgrade,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+pub use passthrough::Passthrough;
+pub use rate_limiter::{ClientId, MethodClass, Quota, RateLimiter};
+pub use rollup_router::RollupRouter;
+use serde_json::{json, Value};
+pub use websocket::WebSocketProxy;
+
+use crate::{
+    error::{error_code, Error, RpcErrorObject},
+    metrics::metrics,
+    rpc::{self, eth},
+    state::AppState,
+};
+
+/// The metric label of calls to methods the RPC server does not serve, which
+/// clients can name freely.
+const PASSTHROUGH_METHOD: &str = "passthrough";
+
+/// The external HTTP endpoint of a rollup.
+///
+/// Calls to the methods of the RPC server are served in-process by their
+/// handlers, so that a failed call is answered with the code and data of its
+/// [`Error`].
+///
+/// Requests over the budget of the [`RateLimiter`] are answered with
+/// [`Error::RateLimitExceeded`] and `429 Too Many Requests` without reaching
+/// the RPC server.
+///
+/// With an [`ApiKeyList`], unknown bearer API keys are answered with `401
+/// Unauthorized` and calls to methods outside the allowlist of the caller with
+/// `403 Forbidden`.
+///
+/// With a [`Passthrough`], calls to methods the RPC server does not serve are
+/// forwarded to the rollup if allowed and answered with `403 Forbidden` if
+/// denied.
+///
+/// Calls in a batch to methods forwarded to the rollup are sent to the rollup
+/// as a single batch, while the others are served concurrently.
+///
+/// With a [`WebSocketProxy`], the same endpoint also accepts WebSocket
+/// connections, over which `eth_subscribe` is proxied to the rollup.
+///
+/// Gateways are served by a [`RollupRouter`], one per hosted rollup.
+pub struct Gateway {
+    inner: Arc<GatewayInner>,
+}
+
+struct GatewayInner {
+    context: AppState,
+    max_batch_size: usize,
+    rate_limiter: Option<RateLimiter>,
+    api_key_list: Option<ApiKeyList>,
+    websocket_proxy: Option<WebSocketProxy>,
+    passthrough: Option<Passthrough>,
+}
+
+impl Clone for Gateway {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Gateway {
+    pub fn new(
+        context: AppState,
+        max_batch_size: usize,
+        rate_limiter: Option<RateLimiter>,
+        api_key_list: Option<ApiKeyList>,
+        websocket_proxy: Option<WebSocketProxy>,
+        passthrough: Option<Passthrough>,
+    ) -> Self {
+        let inner = GatewayInner {
+            context,
+            max_batch_size,
+            rate_limiter,
+            api_key_list,
+            websocket_proxy,
+            passthrough,
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+}
+
+async fn handle_request(
+    gateway: Gateway,
+    remote_address: SocketAddr,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let (status, body) = gateway
+        .process(remote_address.ip(), bearer_token(&headers), body)
+        .await;
+
+    (status, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+async fn handle_websocket(
+    gateway: Gateway,
+    remote_address: SocketAddr,
+    headers: HeaderMap,
+    websocket_upgrade: WebSocketUpgrade,
+) -> Response {
+    let Some(websocket_proxy) = gateway.inner.websocket_proxy.clone() else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+    let api_key = bearer_token(&headers).map(str::to_owned);
+
+    websocket_upgrade.on_upgrade(move |socket| {
+        websocket::serve_connection(
+            gateway,
+            websocket_proxy,
+            socket,
+            remote_address.ip(),
+            api_key,
+        )
+    })
+}
+
+impl Gateway {
+    /// Admits, forwards and answers a single or batch call.
+    async fn process(
+        &self,
+        ip_address: IpAddr,
+        api_key: Option<&str>,
+        body: Bytes,
+    ) -> (StatusCode, Bytes) {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(Value::Array(request_list)) => {
+                self.process_batch(ip_address, api_key, request_list).await
+            }
+            Ok(request) => self.process_call(ip_address, api_key, request).await,
+            Err(_) => {
+                let error_object = RpcErrorObject::new(error_code::PARSE_ERROR, "parse error");
+                (
+                    StatusCode::BAD_REQUEST,
+                    to_bytes(&error_response(Value::Null, error_object)),
+                )
+            }
+        }
+    }
+
+    async fn process_call(
+        &self,
+        ip_address: IpAddr,
+        api_key: Option<&str>,
+        request: Value,
+    ) -> (StatusCode, Bytes) {
+        if let Err((status, response)) =
+            self.admit(ip_address, api_key, id(&request), &[method(&request)])
+        {
+            return (status, to_bytes(&response));
+        }
+
+        let response = if self.is_passthrough(method(&request)) {
+            self.forward_to_rollup(&[(0, &request)])
+                .await
+                .pop()
+                .and_then(|(_, response)| response)
+        } else {
+            Some(self.call(&request).await)
+        };
+
+        match response {
+            Some(response) if !is_notification(&request) => (StatusCode::OK, to_bytes(&response)),
+            _ => (StatusCode::OK, Bytes::new()),
+        }
+    }
+
+    /// Answers the calls of a batch in order, leaving out notifications.
+    ///
+    /// The whole batch is admitted at once, so a batch over the rate limit
+    /// budget of the client is refused as a whole.
+    async fn process_batch(
+        &self,
+        ip_address: IpAddr,
+        api_key: Option<&str>,
+        request_list: Vec<Value>,
+    ) -> (StatusCode, Bytes) {
+        if request_list.is_empty() {
+            let response = error_response(Value::Null, Error::EmptyBatch.to_rpc_error_object());
+            return (StatusCode::BAD_REQUEST, to_bytes(&response));
+        }
+
+        if request_list.len() > self.inner.max_batch_size {
+            let error = Error::BatchTooLarge {
+                size: request_list.len(),
+                max_size: self.inner.max_batch_size,
+            };
+            let response = error_response(Value::Null, error.to_rpc_error_object());
+            return (StatusCode::BAD_REQUEST, to_bytes(&response));
+        }
+
+        let method_list: Vec<&str> = request_list.iter().map(method).collect();
+        if let Err((status, response)) = self.admit(ip_address, api_key, Value::Null, &method_list)
+        {
+            return (status, to_bytes(&response));
+        }
+
+        let (proxied_call_list, native_call_list): (Vec<_>, Vec<_>) = request_list
+            .iter()
+            .enumerate()
+            .partition(|(_, request)| self.is_coalesced(request));
+
+        let native_responses = native_call_list
+            .iter()
+            .map(|(index, request)| async move { (*index, Some(self.call(request).await)) });
+
+        let (proxied_response_list, native_response_list) = tokio::join!(
+            self.forward_to_rollup(&proxied_call_list),
+            futures_util::future::join_all(native_responses),
+        );
+
+        let mut response_list: Vec<Option<Value>> = vec![None; request_list.len()];
+        for (index, response) in proxied_response_list
+            .into_iter()
+            .chain(native_response_list)
+        {
+            response_list[index] = response;
+        }
+
+        let response_list: Vec<Value> = request_list
+            .iter()
+            .zip(response_list)
+            .filter(|(request, _)| !is_notification(request))
+            .filter_map(|(_, response)| response)
+            .collect();
+
+        if response_list.is_empty() {
+            return (StatusCode::OK, Bytes::new());
+        }
+
+        (StatusCode::OK, to_bytes(&Value::Array(response_list)))
+    }
+
+    /// Serves a call through the handler of its method.
+    async fn call(&self, request: &Value) -> Value {
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let error_object = RpcErrorObject::new(error_code::INVALID_REQUEST, "invalid request");
+            return error_response(id(request), error_object);
+        };
+        let params = request.get("params").cloned().unwrap_or_default();
+
+        match rpc::method_router()
+            .call(self.inner.context.clone(), method, params)
+            .await
+        {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "result": result,
+                "id": id(request),
+            }),
+            Err(error) => error_response(id(request), error.to_rpc_error_object()),
+        }
+    }
+
+    /// Sends the calls of a batch to the rollup as a single batch.
+    ///
+    /// Calls are renumbered by their index in the batch, which tells the
+    /// responses apart even if the client reused ids, and the ids of the client
+    /// are restored in the responses.
+    async fn forward_to_rollup(
+        &self,
+        call_list: &[(usize, &Value)],
+    ) -> Vec<(usize, Option<Value>)> {
+        if call_list.is_empty() {
+            return Vec::new();
+        }
+
+        let request_list: Vec<Value> = call_list
+            .iter()
+            .map(|(index, request)| {
+                let mut request = (*request).clone();
+                if let Some(id) = request.get_mut("id") {
+                    *id = json!(index);
+                }
+
+                request
+            })
+            .collect();
+
+        let started_at = Instant::now();
+        let mut response_list: HashMap<usize, Value> = HashMap::new();
+        let mut error_object = RpcErrorObject::new(error_code::INTERNAL_ERROR, "internal error");
+
+        match self
+            .inner
+            .context
+            .rollup_client()
+            .batch_request(&request_list)
+            .await
+        {
+            Ok(rollup_response_list) => {
+                for response in rollup_response_list {
+                    let index = response.get("id").and_then(Value::as_u64);
+
+                    if let Some(index) = index {
+                        response_list.insert(index as usize, response);
+                    }
+                }
+            }
+            Err(error) => {
+                tracing::warn!("Failed to send a batch to the rollup: {:?}", error);
+
+                error_object = Error::from(error).to_rpc_error_object();
+            }
+        }
+
+        call_list
+            .iter()
+            .map(|(index, request)| {
+                let response = match response_list.remove(index) {
+                    Some(mut response) => {
+                        response["id"] = id(request);
+                        response
+                    }
+                    None => error_response(id(request), error_object.clone()),
+                };
+
+                metrics().observe_rpc_request(
+                    self.inner.context.config().rollup_id(),
+                    method_label(method(request)),
+                    response.get("error").is_none(),
+                    started_at.elapsed(),
+                );
+
+                (*index, Some(response))
+            })
+            .collect()
+    }
+
+    /// Returns `true` if a call in a batch is sent to the rollup in the
+    /// coalesced batch.
+    ///
+    /// Calls with a cache policy are left to the RPC server, which serves them
+    /// from the response cache.
+    fn is_coalesced(&self, request: &Value) -> bool {
+        let method = method(request);
+        let parameter = request.get("params").unwrap_or(&Value::Null);
+
+        (eth::is_proxied(method) || self.is_passthrough(method))
+            && eth::CachePolicy::of(method, parameter).is_none()
+    }
+
+    fn is_passthrough(&self, method: &str) -> bool {
+        self.inner
+            .passthrough
+            .as_ref()
+            .is_some_and(|passthrough| passthrough.is_allowed(method))
+    }
+
+    /// Authenticates the caller, refuses denied passthrough methods and charges
+    /// the call to its rate limit budget.
+    fn admit(
+        &self,
+        ip_address: IpAddr,
+        api_key: Option<&str>,
+        id: Value,
+        method_list: &[&str],
+    ) -> Result<(), (StatusCode, Value)> {
+        // Only a key known to the API key list identifies a client, otherwise
+        // rotating random keys would get a fresh bucket on every request.
+        let mut api_key_name = None;
+
+        if let Some(api_key_list) = &self.inner.api_key_list {
+            match api_key_list.authorize(api_key, method_list) {
+                // Rate limit authenticated clients by the name of their key.
+                Ok(name) => api_key_name = name,
+                // Charge rejected keys to the IP address, so that keys cannot be
+                // guessed faster than the rate limit allows.
+                Err(Error::InvalidApiKey) => {
+                    self.charge(ip_address, None, id.clone(), method_list)?;
+
+                    let error = Error::InvalidApiKey;
+                    return Err((
+                        StatusCode::UNAUTHORIZED,
+                        error_response(id, error.to_rpc_error_object()),
+                    ));
+                }
+                Err(error) => {
+                    return Err((
+                        StatusCode::FORBIDDEN,
+                        error_response(id, error.to_rpc_error_object()),
+                    ));
+                }
+            }
+        }
+
+        if let Some(passthrough) = &self.inner.passthrough {
+            if let Some(method) = method_list
+                .iter()
+                .find(|method| passthrough.is_denied(method))
+            {
+                let error = Error::MethodNotAllowed(method.to_string());
+
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    error_response(id, error.to_rpc_error_object()),
+                ));
+            }
+        }
+
+        self.charge(ip_address, api_key_name, id, method_list)
+    }
+
+    fn charge(
+        &self,
+        ip_address: IpAddr,
+        api_key_name: Option<&str>,
+        id: Value,
+        method_list: &[&str],
+    ) -> Result<(), (StatusCode, Value)> {
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            let client_id = rate_limiter.client_id(ip_address, api_key_name);
+
+            check_rate_limit(
+                rate_limiter,
+                self.inner.context.config().rollup_id(),
+                &client_id,
+                id,
+                method_list,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the metric label of `method`, keeping the number of labels bounded.
+fn method_label(method: &str) -> &str {
+    if rpc::is_native(method) {
+        method
+    } else {
+        PASSTHROUGH_METHOD
+    }
+}
+
+fn to_bytes(value: &Value) -> Bytes {
+    Bytes::from(serde_json::to_vec(value).unwrap_or_default())
+}
+
+fn id(request: &Value) -> Value {
+    request.get("id").cloned().unwrap_or_default()
+}
+
+fn method(request: &Value) -> &str {
+    request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// Returns `true` for a call without an id, which is not answered.
+fn is_notification(request: &Value) -> bool {
+    request
+        .as_object()
+        .is_some_and(|request| !request.contains_key("id"))
+}
+
+/// Charges every request of a single or batch call to the client's budgets,
+/// taking nothing from either budget unless both suffice.
+fn check_rate_limit(
+    rate_limiter: &RateLimiter,
+    rollup_id: &str,
+    client_id: &ClientId,
+    id: Value,
+    method_list: &[&str],
+) -> Result<(), (StatusCode, Value)> {
+    let (mut read_cost, mut expensive_cost) = (0, 0);
+    for method in method_list {
+        match MethodClass::of(method) {
+            MethodClass::Read => read_cost += 1,
+            MethodClass::Expensive => expensive_cost += 1,
+        }
+    }
+
+    if let Err(method_class) = rate_limiter.try_acquire(
+        client_id,
+        &[
+            (MethodClass::Read, read_cost),
+            (MethodClass::Expensive, expensive_cost),
+        ],
+    ) {
+        tracing::debug!(
+            "Rate limit exceeded - client_id: {:?}, method_class: {:?}",
+            client_id,
+            method_class
+        );
+        metrics().observe_rate_limited_request(rollup_id, method_class.as_str());
+
+        let response = error_response(id, Error::RateLimitExceeded.to_rpc_error_object());
+        return Err((StatusCode::TOO_MANY_REQUESTS, response));
+    }
+
+    Ok(())
+}
+
+/// Returns the token of an `Authorization: Bearer <token>` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Builds a JSON-RPC error response.
+pub fn error_response(id: Value, error_object: RpcErrorObject) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": error_object,
+        "id": id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, net::Ipv4Addr, time::Duration};
+
+    use super::*;
+    use crate::{
+        client::{
+            rollup::{RollupClient, RollupUpstream},
+            sequencer::{RetryPolicy, SequencerClient},
+            upstream_health::HealthPolicy,
+        },
+        journal::SubmissionJournal,
+        types::{
+            config::{Config, ConfigOption},
+            RollupSelection,
+        },
+    };
+
+    const HEALTH_POLICY: HealthPolicy = HealthPolicy {
+        unhealthy_threshold: 3,
+        healthy_threshold: 2,
+    };
+
+    pub(super) fn gateway(rate_limiter: RateLimiter) -> Gateway {
+        gateway_with_api_keys(rate_limiter, None)
+    }
+
+    fn gateway_with_api_keys(
+        rate_limiter: RateLimiter,
+        api_key_list: Option<ApiKeyList>,
+    ) -> Gateway {
+        let config_path = std::env::temp_dir().join("secure-rpc-gateway-test");
+        let config =
+            Config::from_config_option(config_path.clone(), ConfigOption::default()).unwrap();
+
+        let sequencer_client = SequencerClient::new(
+            config.rollup_id().clone(),
+            config.sequencer_rpc_url_list().clone(),
+            RetryPolicy {
+                max_attempts: 1,
+                attempt_timeout: Duration::from_secs(1),
+                backoff: Duration::ZERO,
+            },
+            HEALTH_POLICY,
+        )
+        .unwrap();
+        let rollup_client = RollupClient::new(
+            config.rollup_id().clone(),
+            vec![RollupUpstream {
+                rpc_url: config.rollup_rpc_url().clone(),
+                weight: 1,
+            }],
+            RollupSelection::default(),
+            HEALTH_POLICY,
+            5,
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        let submission_journal = SubmissionJournal::open(
+            config_path.with_extension("jsonl"),
+            config.submission_journal_retention(),
+            config.submission_journal_capacity(),
+        )
+        .unwrap();
+
+        let context = AppState::new(
+            config,
+            None,
+            None,
+            sequencer_client,
+            rollup_client,
+            submission_journal,
+        );
+
+        Gateway::new(context, 100, Some(rate_limiter), api_key_list, None, None)
+    }
+
+    #[test]
+    fn labels_unregistered_methods_as_passthrough() {
+        assert_eq!(method_label("eth_call"), "eth_call");
+        assert_eq!(
+            method_label("eth_sendRawTransaction"),
+            "eth_sendRawTransaction"
+        );
+        assert_eq!(method_label("eth_getLogs"), PASSTHROUGH_METHOD);
+        assert_eq!(method_label("eth_a1b2c3"), PASSTHROUGH_METHOD);
+    }
+
+    #[tokio::test]
+    async fn answers_with_the_error_object_of_the_handler() {
+        let quota = Quota {
+            requests_per_second: 100,
+            burst: 100,
+        };
+        let gateway = gateway(RateLimiter::new(quota, quota, false));
+
+        let response = gateway
+            .call(&json!({
+                "jsonrpc": "2.0",
+                "method": "secure_getSubmission",
+                "params": [],
+                "id": 1,
+            }))
+            .await;
+        assert_eq!(response["id"], json!(1));
+        assert_eq!(response["error"]["code"], json!(error_code::INVALID_PARAMS));
+        assert_eq!(
+            response["error"]["data"]["kind"],
+            json!("EmptyTransactionHash")
+        );
+
+        let response = gateway
+            .call(&json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getLogs",
+                "params": [],
+                "id": 2,
+            }))
+            .await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(error_code::METHOD_NOT_FOUND)
+        );
+        assert_eq!(response["error"]["data"]["method"], json!("eth_getLogs"));
+    }
+
+    #[test]
+    fn rate_limits_unverified_api_keys_by_ip_address() {
+        let quota = Quota {
+            requests_per_second: 1,
+            burst: 1,
+        };
+        let gateway = gateway(RateLimiter::new(quota, quota, true));
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        assert!(gateway
+            .admit(ip_address, Some("random-1"), json!(1), &["eth_call"])
+            .is_ok());
+
+        let (status, _) = gateway
+            .admit(ip_address, Some("random-2"), json!(2), &["eth_call"])
+            .unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn rate_limits_invalid_api_keys_before_refusing_them() {
+        let path = std::env::temp_dir().join(format!(
+            "secure-rpc-gateway-api-keys-{}.toml",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            r#"
+            [[api_key]]
+            name = "operator"
+            key = "secret"
+            method_list = ["*"]
+            "#,
+        )
+        .unwrap();
+        let api_key_list = ApiKeyList::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let quota = Quota {
+            requests_per_second: 1,
+            burst: 1,
+        };
+        let gateway =
+            gateway_with_api_keys(RateLimiter::new(quota, quota, true), Some(api_key_list));
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let (status, _) = gateway
+            .admit(ip_address, Some("guess-1"), json!(1), &["eth_call"])
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = gateway
+            .admit(ip_address, Some("guess-2"), json!(2), &["eth_call"])
+            .unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // The operator key has its own budget.
+        assert!(gateway
+            .admit(ip_address, Some("secret"), json!(3), &["eth_call"])
+            .is_ok());
+    }
+}
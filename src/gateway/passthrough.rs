@@ -0,0 +1,79 @@
+use super::auth::matches_any;
+use crate::rpc;
+
+/// Methods the RPC server does not serve that are forwarded to the rollup as
+/// is.
+///
+/// A method pattern is either `*`, a prefix ending with `*` or an exact method.
+/// The deny list wins over the allow list, and neither applies to the methods
+/// registered on the RPC server.
+#[derive(Clone, Debug)]
+pub struct Passthrough {
+    pub allow_pattern_list: Vec<String>,
+    pub deny_pattern_list: Vec<String>,
+}
+
+impl Passthrough {
+    /// Returns `true` if calls to `method` are forwarded to the rollup.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        !rpc::is_native(method)
+            && matches_any(&self.allow_pattern_list, method)
+            && !matches_any(&self.deny_pattern_list, method)
+    }
+
+    /// Returns `true` if calls to `method` are refused without reaching the
+    /// rollup.
+    pub fn is_denied(&self, method: &str) -> bool {
+        !rpc::is_native(method) && matches_any(&self.deny_pattern_list, method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passthrough(allow_pattern_list: &[&str], deny_pattern_list: &[&str]) -> Passthrough {
+        Passthrough {
+            allow_pattern_list: allow_pattern_list.iter().map(|p| p.to_string()).collect(),
+            deny_pattern_list: deny_pattern_list.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn matches_exact_and_prefix_patterns() {
+        let pattern_list = vec!["eth_*".to_owned(), "debug_traceTransaction".to_owned()];
+
+        assert!(matches_any(&pattern_list, "eth_getLogs"));
+        assert!(matches_any(&pattern_list, "debug_traceTransaction"));
+        assert!(!matches_any(&pattern_list, "debug_traceCall"));
+        assert!(!matches_any(&pattern_list, "net_version"));
+        assert!(matches_any(&["*".to_owned()], "anything"));
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let passthrough = passthrough(&["eth_*"], &["eth_send*", "eth_sign*"]);
+
+        assert!(passthrough.is_allowed("eth_getLogs"));
+        assert!(!passthrough.is_denied("eth_getLogs"));
+
+        for method in [
+            "eth_sendRawTransactionConditional",
+            "eth_sendPrivateTransaction",
+            "eth_sendUserOperation",
+            "eth_signTypedData_v4",
+        ] {
+            assert!(!passthrough.is_allowed(method), "{method}");
+            assert!(passthrough.is_denied(method), "{method}");
+        }
+    }
+
+    #[test]
+    fn ignores_native_methods() {
+        let passthrough = passthrough(&["*"], &["eth_send*"]);
+
+        assert!(!passthrough.is_allowed("eth_call"));
+        assert!(!passthrough.is_denied("eth_sendRawTransaction"));
+        assert!(!passthrough.is_denied("eth_sendBundle"));
+    }
+}
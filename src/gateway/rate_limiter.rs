@@ -0,0 +1,295 @@
+use std::{
+    net::IpAddr,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use lru::LruCache;
+
+/// The number of client buckets kept in memory. Idle clients are evicted
+/// first, which only ever gives them a full bucket back.
+const BUCKET_CAPACITY: usize = 65536;
+
+/// Token bucket rate limiter with separate budgets for cheap reads and for
+/// requests that encrypt, decrypt or submit transactions.
+pub struct RateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+struct RateLimiterInner {
+    read_quota: Quota,
+    expensive_quota: Quota,
+    is_keyed_by_api_key: bool,
+    buckets: Mutex<LruCache<(ClientId, MethodClass), TokenBucket>>,
+}
+
+impl Clone for RateLimiter {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Quota {
+    pub requests_per_second: u32,
+    /// The number of requests a client can make at once after being idle.
+    pub burst: u32,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ClientId {
+    IpAddress(IpAddr),
+    ApiKey(String),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MethodClass {
+    Read,
+    Expensive,
+}
+
+impl MethodClass {
+    /// `eth_*` methods other than `eth_send*` are forwarded to the rollup as
+    /// is. Everything else costs encryption, key generation or sequencer work.
+    pub fn of(method: &str) -> Self {
+        if method.starts_with("eth_") && !method.starts_with("eth_send") {
+            Self::Read
+        } else {
+            Self::Expensive
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Expensive => "expensive",
+        }
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(read_quota: Quota, expensive_quota: Quota, is_keyed_by_api_key: bool) -> Self {
+        let inner = RateLimiterInner {
+            read_quota,
+            expensive_quota,
+            is_keyed_by_api_key,
+            buckets: Mutex::new(LruCache::new(NonZeroUsize::new(BUCKET_CAPACITY).unwrap())),
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Identifies the client by the name of its authorized API key when
+    /// configured to and one is given, and by its IP address otherwise.
+    ///
+    /// `api_key_name` must come from [`ApiKeyList::authorize`], never from the
+    /// request itself.
+    ///
+    /// [`ApiKeyList::authorize`]: super::ApiKeyList::authorize
+    pub fn client_id(&self, ip_address: IpAddr, api_key_name: Option<&str>) -> ClientId {
+        match api_key_name {
+            Some(api_key_name) if self.inner.is_keyed_by_api_key => {
+                ClientId::ApiKey(api_key_name.to_owned())
+            }
+            _ => ClientId::IpAddress(ip_address),
+        }
+    }
+
+    /// Takes the tokens of `cost_list` from the buckets of the client. Returns
+    /// the first method class whose bucket does not hold enough and takes
+    /// nothing from any bucket in that case.
+    pub fn try_acquire(
+        &self,
+        client_id: &ClientId,
+        cost_list: &[(MethodClass, u32)],
+    ) -> Result<(), MethodClass> {
+        self.try_acquire_at(client_id, cost_list, Instant::now())
+    }
+
+    fn try_acquire_at(
+        &self,
+        client_id: &ClientId,
+        cost_list: &[(MethodClass, u32)],
+        now: Instant,
+    ) -> Result<(), MethodClass> {
+        let mut buckets = self.inner.buckets.lock().unwrap();
+
+        for &(method_class, cost) in cost_list {
+            if cost == 0 {
+                continue;
+            }
+
+            let quota = self.quota(method_class);
+            let bucket =
+                buckets.get_or_insert_mut((client_id.clone(), method_class), || TokenBucket {
+                    tokens: quota.burst as f64,
+                    updated_at: now,
+                });
+
+            let refilled = now.duration_since(bucket.updated_at).as_secs_f64()
+                * quota.requests_per_second as f64;
+            bucket.tokens = (bucket.tokens + refilled).min(quota.burst as f64);
+            bucket.updated_at = now;
+
+            if bucket.tokens < cost as f64 {
+                return Err(method_class);
+            }
+        }
+
+        for &(method_class, cost) in cost_list {
+            if let Some(bucket) = buckets.get_mut(&(client_id.clone(), method_class)) {
+                bucket.tokens -= cost as f64;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn quota(&self, method_class: MethodClass) -> Quota {
+        match method_class {
+            MethodClass::Read => self.inner.read_quota,
+            MethodClass::Expensive => self.inner.expensive_quota,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use super::*;
+
+    const IP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    fn rate_limiter(is_keyed_by_api_key: bool) -> RateLimiter {
+        RateLimiter::new(
+            Quota {
+                requests_per_second: 10,
+                burst: 2,
+            },
+            Quota {
+                requests_per_second: 1,
+                burst: 1,
+            },
+            is_keyed_by_api_key,
+        )
+    }
+
+    #[test]
+    fn refills_tokens_over_time() {
+        let rate_limiter = rate_limiter(false);
+        let client_id = ClientId::IpAddress(IP_ADDRESS);
+        let now = Instant::now();
+
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 2)], now)
+            .is_ok());
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 1)], now)
+            .is_err());
+
+        // 10 requests per second refill one token every 100ms.
+        let later = now + Duration::from_millis(100);
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 1)], later)
+            .is_ok());
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 1)], later)
+            .is_err());
+
+        // A long idle period never refills more than the burst.
+        let much_later = later + Duration::from_secs(60);
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 3)], much_later)
+            .is_err());
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 2)], much_later)
+            .is_ok());
+    }
+
+    #[test]
+    fn keeps_separate_budgets_per_method_class() {
+        let rate_limiter = rate_limiter(false);
+        let client_id = ClientId::IpAddress(IP_ADDRESS);
+        let now = Instant::now();
+
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Expensive, 1)], now)
+            .is_ok());
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Expensive, 1)], now)
+            .is_err());
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 2)], now)
+            .is_ok());
+    }
+
+    #[test]
+    fn takes_nothing_unless_every_budget_suffices() {
+        let rate_limiter = rate_limiter(false);
+        let client_id = ClientId::IpAddress(IP_ADDRESS);
+        let now = Instant::now();
+
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Expensive, 1)], now)
+            .is_ok());
+        assert_eq!(
+            rate_limiter.try_acquire_at(
+                &client_id,
+                &[(MethodClass::Read, 1), (MethodClass::Expensive, 1)],
+                now
+            ),
+            Err(MethodClass::Expensive)
+        );
+        assert!(rate_limiter
+            .try_acquire_at(&client_id, &[(MethodClass::Read, 2)], now)
+            .is_ok());
+    }
+
+    #[test]
+    fn classifies_methods() {
+        assert_eq!(MethodClass::of("eth_call"), MethodClass::Read);
+        assert_eq!(MethodClass::of("eth_getBalance"), MethodClass::Read);
+        assert_eq!(
+            MethodClass::of("eth_sendRawTransaction"),
+            MethodClass::Expensive
+        );
+        assert_eq!(MethodClass::of("eth_sendBundle"), MethodClass::Expensive);
+        assert_eq!(
+            MethodClass::of("send_encrypted_transaction"),
+            MethodClass::Expensive
+        );
+        assert_eq!(
+            MethodClass::of("decrypt_transaction"),
+            MethodClass::Expensive
+        );
+    }
+
+    #[test]
+    fn falls_back_to_ip_address_without_api_key_name() {
+        let keyed_rate_limiter = rate_limiter(true);
+
+        assert_eq!(
+            keyed_rate_limiter.client_id(IP_ADDRESS, None),
+            ClientId::IpAddress(IP_ADDRESS)
+        );
+        assert_eq!(
+            keyed_rate_limiter.client_id(IP_ADDRESS, Some("operator")),
+            ClientId::ApiKey("operator".into())
+        );
+        assert_eq!(
+            rate_limiter(false).client_id(IP_ADDRESS, Some("operator")),
+            ClientId::IpAddress(IP_ADDRESS)
+        );
+    }
+}
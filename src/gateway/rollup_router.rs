@@ -0,0 +1,148 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use axum::{
+    body::Bytes,
+    extract::{ws::WebSocketUpgrade, ConnectInfo, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use super::{handle_request, handle_websocket, Gateway};
+use crate::error::Error;
+
+/// Routes requests on the external endpoint to the [`Gateway`] of each hosted
+/// rollup.
+///
+/// A rollup is selected by the `/rollup/<rollup_id>` path, or on `/` by the
+/// `Host` header registered for it. Requests on `/` for any other host go to
+/// the default rollup.
+pub struct RollupRouter {
+    default_gateway: Gateway,
+    gateway_list: HashMap<String, Gateway>,
+    host_list: HashMap<String, String>,
+}
+
+impl RollupRouter {
+    pub fn new(rollup_id: impl AsRef<str>, default_gateway: Gateway) -> Self {
+        let mut gateway_list = HashMap::new();
+        gateway_list.insert(rollup_id.as_ref().to_owned(), default_gateway.clone());
+
+        Self {
+            default_gateway,
+            gateway_list,
+            host_list: HashMap::new(),
+        }
+    }
+
+    /// Hosts another rollup, reachable on `/` by `host` if given.
+    pub fn add(&mut self, rollup_id: impl AsRef<str>, host: Option<String>, gateway: Gateway) {
+        let rollup_id = rollup_id.as_ref().to_owned();
+
+        if let Some(host) = host {
+            self.host_list
+                .insert(normalize_host(&host), rollup_id.clone());
+        }
+
+        self.gateway_list.insert(rollup_id, gateway);
+    }
+
+    pub async fn serve(self, listen_address: impl AsRef<str>) -> Result<JoinHandle<()>, Error> {
+        let router = Router::new()
+            .route(
+                "/",
+                get(handle_websocket_by_host).post(handle_request_by_host),
+            )
+            .route(
+                "/rollup/:rollup_id",
+                get(handle_websocket_by_path).post(handle_request_by_path),
+            )
+            .with_state(Arc::new(self));
+
+        let listener = TcpListener::bind(listen_address.as_ref()).await?;
+
+        Ok(tokio::spawn(async move {
+            let service = router.into_make_service_with_connect_info::<SocketAddr>();
+
+            if let Err(error) = axum::serve(listener, service).await {
+                tracing::error!("Gateway stopped: {:?}", error);
+            }
+        }))
+    }
+
+    fn gateway_by_host(&self, headers: &HeaderMap) -> Gateway {
+        headers
+            .get(header::HOST)
+            .and_then(|host| host.to_str().ok())
+            .and_then(|host| self.host_list.get(&normalize_host(host)))
+            .and_then(|rollup_id| self.gateway_list.get(rollup_id))
+            .unwrap_or(&self.default_gateway)
+            .clone()
+    }
+
+    fn gateway_by_rollup_id(&self, rollup_id: &str) -> Option<Gateway> {
+        self.gateway_list.get(rollup_id).cloned()
+    }
+}
+
+async fn handle_request_by_host(
+    State(rollup_router): State<Arc<RollupRouter>>,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let gateway = rollup_router.gateway_by_host(&headers);
+
+    handle_request(gateway, remote_address, headers, body).await
+}
+
+async fn handle_websocket_by_host(
+    State(rollup_router): State<Arc<RollupRouter>>,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    websocket_upgrade: WebSocketUpgrade,
+) -> Response {
+    let gateway = rollup_router.gateway_by_host(&headers);
+
+    handle_websocket(gateway, remote_address, headers, websocket_upgrade).await
+}
+
+async fn handle_request_by_path(
+    State(rollup_router): State<Arc<RollupRouter>>,
+    Path(rollup_id): Path<String>,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    match rollup_router.gateway_by_rollup_id(&rollup_id) {
+        Some(gateway) => handle_request(gateway, remote_address, headers, body).await,
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn handle_websocket_by_path(
+    State(rollup_router): State<Arc<RollupRouter>>,
+    Path(rollup_id): Path<String>,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    websocket_upgrade: WebSocketUpgrade,
+) -> Response {
+    match rollup_router.gateway_by_rollup_id(&rollup_id) {
+        Some(gateway) => {
+            handle_websocket(gateway, remote_address, headers, websocket_upgrade).await
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Strips the port from a `Host` header value and lowercases it.
+fn normalize_host(host: &str) -> String {
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+
+    host.to_lowercase()
+}
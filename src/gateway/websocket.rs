@@ -0,0 +1,372 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+};
+
+use axum::{
+    body::Bytes,
+    extract::ws::{Message, WebSocket},
+};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async, tungstenite::Message as UpstreamMessage, MaybeTlsStream, WebSocketStream,
+};
+
+use super::{error_response, Gateway};
+use crate::error::Error;
+
+const SUBSCRIBE_METHOD: &str = "eth_subscribe";
+const UNSUBSCRIBE_METHOD: &str = "eth_unsubscribe";
+
+type UpstreamStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Where and how many subscriptions a WebSocket connection may open.
+#[derive(Clone, Debug)]
+pub struct WebSocketProxy {
+    pub rollup_websocket_url: String,
+    pub max_subscriptions: usize,
+}
+
+/// Serves a WebSocket connection.
+///
+/// `eth_subscribe` and `eth_unsubscribe` are proxied over a connection to the
+/// rollup WebSocket url opened on the first subscription, and notifications
+/// are relayed back as is. Every other call is served like an HTTP call. When
+/// the rollup closes its connection, the client connection is closed as well
+/// so that the client reconnects and subscribes again.
+pub async fn serve_connection(
+    gateway: Gateway,
+    websocket_proxy: WebSocketProxy,
+    socket: WebSocket,
+    ip_address: IpAddr,
+    api_key: Option<String>,
+) {
+    let (mut client_sink, mut client_stream) = socket.split();
+    let mut upstream_stream: Option<SplitStream<UpstreamStream>> = None;
+    let mut connection = Connection {
+        gateway,
+        websocket_proxy,
+        ip_address,
+        api_key,
+        upstream_sink: None,
+        subscription_list: HashSet::new(),
+        pending_subscribe_list: HashSet::new(),
+        pending_unsubscribe_list: HashMap::new(),
+    };
+
+    loop {
+        tokio::select! {
+            message = client_stream.next() => {
+                let request = match message {
+                    Some(Ok(Message::Text(request))) => request,
+                    Some(Ok(Message::Binary(request))) => match String::from_utf8(request) {
+                        Ok(request) => request,
+                        Err(_) => continue,
+                    },
+                    Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                };
+
+                if let Some(response) = connection.handle_request(request, &mut upstream_stream).await {
+                    if client_sink.send(Message::Text(response)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            message = next_upstream_message(&mut upstream_stream) => {
+                match message {
+                    Some(Ok(UpstreamMessage::Text(message))) => {
+                        connection.track_response(&message);
+
+                        if client_sink.send(Message::Text(message)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(UpstreamMessage::Close(_))) | Some(Err(_)) | None => {
+                        tracing::warn!("Rollup websocket connection closed");
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+
+    let _ = client_sink.close().await;
+    if let Some(mut upstream_sink) = connection.upstream_sink.take() {
+        let _ = upstream_sink.close().await;
+    }
+}
+
+async fn next_upstream_message(
+    upstream_stream: &mut Option<SplitStream<UpstreamStream>>,
+) -> Option<Result<UpstreamMessage, tokio_tungstenite::tungstenite::Error>> {
+    match upstream_stream {
+        Some(upstream_stream) => upstream_stream.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+struct Connection {
+    gateway: Gateway,
+    websocket_proxy: WebSocketProxy,
+    ip_address: IpAddr,
+    api_key: Option<String>,
+    upstream_sink: Option<SplitSink<UpstreamStream, UpstreamMessage>>,
+    /// Active subscription ids.
+    subscription_list: HashSet<String>,
+    /// Request ids of `eth_subscribe` calls waiting for the rollup.
+    pending_subscribe_list: HashSet<String>,
+    /// Subscription ids by the request id of `eth_unsubscribe` calls.
+    pending_unsubscribe_list: HashMap<String, String>,
+}
+
+impl Connection {
+    /// Returns the response to send back, or `None` if the rollup answers.
+    async fn handle_request(
+        &mut self,
+        request: String,
+        upstream_stream: &mut Option<SplitStream<UpstreamStream>>,
+    ) -> Option<String> {
+        let parsed_request = serde_json::from_str::<Value>(&request).unwrap_or_default();
+        let method = parsed_request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        if method != SUBSCRIBE_METHOD && method != UNSUBSCRIBE_METHOD {
+            let (_, response) = self
+                .gateway
+                .process(
+                    self.ip_address,
+                    self.api_key.as_deref(),
+                    Bytes::from(request),
+                )
+                .await;
+
+            // A batch of notifications is not answered.
+            if response.is_empty() {
+                return None;
+            }
+
+            return Some(String::from_utf8_lossy(&response).into_owned());
+        }
+
+        let id = parsed_request.get("id").cloned().unwrap_or_default();
+
+        if let Err((_, response)) = self.gateway.admit(
+            self.ip_address,
+            self.api_key.as_deref(),
+            id.clone(),
+            &[method],
+        ) {
+            return Some(response.to_string());
+        }
+
+        if method == SUBSCRIBE_METHOD {
+            let subscription_count =
+                self.subscription_list.len() + self.pending_subscribe_list.len();
+            if subscription_count >= self.websocket_proxy.max_subscriptions {
+                let error_object = Error::SubscriptionLimitExceeded.to_rpc_error_object();
+                return Some(error_response(id, error_object).to_string());
+            }
+
+            self.pending_subscribe_list.insert(id.to_string());
+        } else {
+            let subscription_id = parsed_request
+                .get("params")
+                .and_then(|params| params.get(0))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned();
+
+            self.pending_unsubscribe_list
+                .insert(id.to_string(), subscription_id);
+        }
+
+        if let Err(error) = self.send_upstream(request, upstream_stream).await {
+            tracing::warn!("Failed to proxy {} to the rollup: {:?}", method, error);
+
+            self.pending_subscribe_list.remove(&id.to_string());
+            self.pending_unsubscribe_list.remove(&id.to_string());
+
+            let error_object = Error::RollupWebSocketUnavailable.to_rpc_error_object();
+            return Some(error_response(id, error_object).to_string());
+        }
+
+        None
+    }
+
+    async fn send_upstream(
+        &mut self,
+        request: String,
+        upstream_stream: &mut Option<SplitStream<UpstreamStream>>,
+    ) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+        if self.upstream_sink.is_none() {
+            let (upstream, _) = connect_async(&self.websocket_proxy.rollup_websocket_url).await?;
+            let (upstream_sink, stream) = upstream.split();
+
+            self.upstream_sink = Some(upstream_sink);
+            *upstream_stream = Some(stream);
+        }
+
+        self.upstream_sink
+            .as_mut()
+            .unwrap()
+            .send(UpstreamMessage::Text(request))
+            .await
+    }
+
+    /// Keeps the subscription count in step with the answers of the rollup.
+    fn track_response(&mut self, message: &str) {
+        let Ok(response) = serde_json::from_str::<Value>(message) else {
+            return;
+        };
+        let Some(id) = response.get("id").map(Value::to_string) else {
+            return;
+        };
+
+        if self.pending_subscribe_list.remove(&id) {
+            if let Some(subscription_id) = response.get("result").and_then(Value::as_str) {
+                self.subscription_list.insert(subscription_id.to_owned());
+            }
+        } else if let Some(subscription_id) = self.pending_unsubscribe_list.remove(&id) {
+            if response.get("result").and_then(Value::as_bool) == Some(true) {
+                self.subscription_list.remove(&subscription_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use axum::{extract::WebSocketUpgrade, routing::get, Router};
+    use serde_json::json;
+    use tokio::{net::TcpListener, task::JoinHandle};
+
+    use super::*;
+    use crate::gateway::{tests::gateway, Quota, RateLimiter};
+
+    const IP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    fn websocket_proxy(rollup_websocket_url: String) -> WebSocketProxy {
+        WebSocketProxy {
+            rollup_websocket_url,
+            max_subscriptions: 2,
+        }
+    }
+
+    fn test_gateway() -> Gateway {
+        let quota = Quota {
+            requests_per_second: 100,
+            burst: 100,
+        };
+
+        gateway(RateLimiter::new(quota, quota, false))
+    }
+
+    /// A rollup accepting one WebSocket connection, which answers every call
+    /// with subscription id `0x1`. The task ends when the connection closes.
+    async fn rollup() -> (String, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let rollup_websocket_url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let rollup = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            while let Some(Ok(message)) = websocket.next().await {
+                if let UpstreamMessage::Text(request) = message {
+                    let request: Value = serde_json::from_str(&request).unwrap();
+                    let response =
+                        json!({ "jsonrpc": "2.0", "id": request["id"], "result": "0x1" });
+
+                    let _ = websocket
+                        .send(UpstreamMessage::Text(response.to_string()))
+                        .await;
+                }
+            }
+        });
+
+        (rollup_websocket_url, rollup)
+    }
+
+    #[tokio::test]
+    async fn tracks_subscriptions_from_rollup_answers() {
+        let mut connection = Connection {
+            gateway: test_gateway(),
+            websocket_proxy: websocket_proxy("ws://127.0.0.1:1".into()),
+            ip_address: IP_ADDRESS,
+            api_key: None,
+            upstream_sink: None,
+            subscription_list: HashSet::new(),
+            pending_subscribe_list: HashSet::from([json!(1).to_string()]),
+            pending_unsubscribe_list: HashMap::from([(json!(2).to_string(), "0x1".to_owned())]),
+        };
+
+        connection.track_response(r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#);
+        assert!(connection.pending_subscribe_list.is_empty());
+        assert_eq!(connection.subscription_list.len(), 1);
+
+        connection.track_response(r#"{"jsonrpc":"2.0","id":2,"result":true}"#);
+        assert!(connection.pending_unsubscribe_list.is_empty());
+        assert!(connection.subscription_list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drops_the_subscriptions_of_a_disconnected_client() {
+        let (rollup_websocket_url, rollup) = rollup().await;
+
+        let gateway = test_gateway();
+        let websocket_proxy = websocket_proxy(rollup_websocket_url);
+        let router = Router::new().route(
+            "/",
+            get(move |upgrade: WebSocketUpgrade| {
+                let gateway = gateway.clone();
+                let websocket_proxy = websocket_proxy.clone();
+
+                async move {
+                    upgrade.on_upgrade(move |socket| {
+                        serve_connection(gateway, websocket_proxy, socket, IP_ADDRESS, None)
+                    })
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let (mut client, _) = connect_async(&url).await.unwrap();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": SUBSCRIBE_METHOD,
+            "params": ["newHeads"],
+            "id": 1,
+        });
+        client
+            .send(UpstreamMessage::Text(request.to_string()))
+            .await
+            .unwrap();
+
+        let Some(Ok(UpstreamMessage::Text(response))) = client.next().await else {
+            panic!("expected the subscription id");
+        };
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"], json!("0x1"));
+
+        // Closing the client closes the rollup connection, which ends every
+        // subscription opened through it.
+        client.close(None).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), rollup)
+            .await
+            .expect("the rollup connection to be closed")
+            .unwrap();
+    }
+}
@@ -0,0 +1,542 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use lru::LruCache;
+use sequencer::types::{EthRawTransaction, OrderCommitment, RawTransaction};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    validation::{bundle_hash, decode_raw_transaction, parse_bundle},
+};
+
+/// How often the journal drops submissions older than the retention period.
+const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Append-only record of every transaction submitted to the sequencer.
+///
+/// Records are appended to a JSON lines file and indexed by rollup id and
+/// transaction hash in memory, so that rollups sharing the journal do not see
+/// each other's submissions. The file is replayed on startup, so a later record
+/// for the same transaction replaces an earlier one.
+///
+/// The index holds at most `capacity` submissions, dropping the least recently
+/// submitted ones first.
+///
+/// Records are written by a dedicated thread, off the async request path. The
+/// same thread compacts the file and the index every [`COMPACTION_INTERVAL`],
+/// dropping submissions older than the retention period or beyond the
+/// capacity.
+pub struct SubmissionJournal {
+    inner: Arc<SubmissionJournalInner>,
+}
+
+struct SubmissionJournalInner {
+    index: Arc<RwLock<Index>>,
+    retention: Duration,
+    sender: Option<mpsc::Sender<SubmissionRecord>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// A rollup id and a lowercase transaction hash.
+type JournalKey = (String, String);
+
+type Index = LruCache<JournalKey, SubmissionRecord>;
+
+impl Clone for SubmissionJournal {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for SubmissionJournalInner {
+    /// Waits for the writer to journal the records still queued.
+    fn drop(&mut self) {
+        self.sender.take();
+
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SubmissionRecord {
+    pub transaction_hash: String,
+    pub rollup_id: String,
+    pub sender: Option<String>,
+    /// The bundle hash, for a bundle and for each transaction of a bundle.
+    #[serde(default)]
+    pub bundle_hash: Option<String>,
+    /// The SKDE encryption key id, for encrypted submissions.
+    pub encryption_key_id: Option<u64>,
+    pub sequencer_rpc_url: String,
+    pub order_commitment: OrderCommitment,
+    /// Whether the order commitment passed verification, if it was verified.
+    #[serde(default)]
+    pub is_order_commitment_valid: Option<bool>,
+    /// Unix time in milliseconds when the submission was received.
+    pub submitted_at: u64,
+    /// Unix time in milliseconds when the order commitment was received.
+    pub committed_at: u64,
+}
+
+impl SubmissionRecord {
+    /// Returns the records of a submission: one for a transaction, or one for
+    /// a bundle under its bundle hash and one for each of its transactions.
+    pub fn list(
+        raw_transaction: &RawTransaction,
+        rollup_id: &str,
+        encryption_key_id: Option<u64>,
+        sequencer_rpc_url: &str,
+        order_commitment: &OrderCommitment,
+        submitted_at: u64,
+    ) -> Vec<Self> {
+        let committed_at = now();
+        let record = |transaction_hash: String, sender, bundle_hash| Self {
+            transaction_hash: transaction_hash.to_lowercase(),
+            rollup_id: rollup_id.to_owned(),
+            sender,
+            bundle_hash,
+            encryption_key_id,
+            sequencer_rpc_url: sequencer_rpc_url.to_owned(),
+            order_commitment: order_commitment.clone(),
+            is_order_commitment_valid: None,
+            submitted_at,
+            committed_at,
+        };
+
+        match raw_transaction {
+            RawTransaction::Eth(raw_transaction) => vec![record(
+                raw_transaction.raw_transaction_hash().as_string(),
+                sender(&raw_transaction.0),
+                None,
+            )],
+            RawTransaction::EthBundle(raw_bundle) => {
+                let Ok(raw_transaction_list) = parse_bundle(&raw_bundle.0) else {
+                    return Vec::new();
+                };
+                let Ok(bundle_hash) = bundle_hash(&raw_transaction_list) else {
+                    return Vec::new();
+                };
+
+                let mut record_list =
+                    vec![record(bundle_hash.clone(), None, Some(bundle_hash.clone()))];
+                for raw_transaction in raw_transaction_list {
+                    let transaction_hash = EthRawTransaction(raw_transaction.clone())
+                        .raw_transaction_hash()
+                        .as_string();
+
+                    record_list.push(record(
+                        transaction_hash,
+                        sender(&raw_transaction),
+                        Some(bundle_hash.clone()),
+                    ));
+                }
+
+                record_list
+            }
+        }
+    }
+
+    fn key(&self) -> JournalKey {
+        (self.rollup_id.clone(), self.transaction_hash.to_lowercase())
+    }
+
+    fn is_expired(&self, retention: Duration) -> bool {
+        self.submitted_at
+            .saturating_add(retention.as_millis() as u64)
+            < now()
+    }
+}
+
+impl SubmissionJournal {
+    /// Opens the journal at `path`, creating it if it does not exist.
+    /// Submissions older than `retention` are dropped.
+    ///
+    /// A malformed final line without a line break is a record torn by a crash
+    /// while it was appended, and is dropped. Any other malformed record fails
+    /// the open, rather than being silently lost when the file is compacted.
+    pub fn open(
+        path: impl AsRef<Path>,
+        retention: Duration,
+        capacity: usize,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref().to_owned();
+
+        let mut index = Index::new(NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN));
+        if path.exists() {
+            let journal_string = fs::read_to_string(&path).map_err(Error::Journal)?;
+            let line_count = journal_string.lines().count();
+
+            for (line_number, line) in journal_string.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match serde_json::from_str::<SubmissionRecord>(line) {
+                    Ok(record) => {
+                        index.put(record.key(), record);
+                    }
+                    Err(error)
+                        if line_number + 1 == line_count && !journal_string.ends_with('\n') =>
+                    {
+                        tracing::warn!("Dropping the torn last record of {:?}: {:?}", path, error);
+                    }
+                    Err(error) => {
+                        return Err(Error::Journal(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "malformed record on line {} of {:?}: {}",
+                                line_number + 1,
+                                path,
+                                error
+                            ),
+                        )))
+                    }
+                }
+            }
+        }
+        drop_expired(&mut index, retention);
+
+        let record_list: Vec<SubmissionRecord> = index
+            .iter()
+            .rev()
+            .map(|(_, record)| record.clone())
+            .collect();
+        let file = write_compacted(&path, &record_list).map_err(Error::Journal)?;
+        let index = Arc::new(RwLock::new(index));
+
+        let (sender, receiver) = mpsc::channel();
+        let writer = Writer {
+            path,
+            file: BufWriter::new(file),
+            index: index.clone(),
+            retention,
+        };
+        let writer = thread::Builder::new()
+            .name("submission-journal".into())
+            .spawn(move || writer.run(receiver))
+            .map_err(Error::Journal)?;
+
+        let inner = SubmissionJournalInner {
+            index,
+            retention,
+            sender: Some(sender),
+            writer: Some(writer),
+        };
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Indexes `record` and queues it for the writer. Failures are logged
+    /// rather than returned, because the transaction has already been ordered
+    /// by then.
+    pub fn record(&self, record: SubmissionRecord) {
+        self.inner
+            .index
+            .write()
+            .unwrap()
+            .put(record.key(), record.clone());
+
+        let is_queued = self
+            .inner
+            .sender
+            .as_ref()
+            .is_some_and(|sender| sender.send(record.clone()).is_ok());
+        if !is_queued {
+            tracing::error!(
+                "Failed to journal the submission of {}: the journal writer has stopped",
+                record.transaction_hash
+            );
+        }
+    }
+
+    pub fn get(&self, rollup_id: &str, transaction_hash: &str) -> Option<SubmissionRecord> {
+        let key = (rollup_id.to_owned(), transaction_hash.to_lowercase());
+
+        self.inner
+            .index
+            .read()
+            .unwrap()
+            .peek(&key)
+            .filter(|record| !record.is_expired(self.inner.retention))
+            .cloned()
+    }
+}
+
+struct Writer {
+    path: PathBuf,
+    file: BufWriter<File>,
+    index: Arc<RwLock<Index>>,
+    retention: Duration,
+}
+
+impl Writer {
+    /// Appends queued records until every [`SubmissionJournal`] is dropped.
+    fn run(mut self, receiver: mpsc::Receiver<SubmissionRecord>) {
+        let mut compacted_at = Instant::now();
+
+        loop {
+            match receiver.recv_timeout(COMPACTION_INTERVAL) {
+                Ok(record) => {
+                    if let Err(error) = self.append(&record) {
+                        tracing::error!(
+                            "Failed to journal the submission of {}: {:?}",
+                            record.transaction_hash,
+                            error
+                        );
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if compacted_at.elapsed() >= COMPACTION_INTERVAL {
+                if let Err(error) = self.compact() {
+                    tracing::error!("Failed to compact the submission journal: {:?}", error);
+                }
+                compacted_at = Instant::now();
+            }
+        }
+    }
+
+    fn append(&mut self, record: &SubmissionRecord) -> Result<(), io::Error> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()
+    }
+
+    fn compact(&mut self) -> Result<(), io::Error> {
+        let record_list: Vec<SubmissionRecord> = {
+            let mut index = self.index.write().unwrap();
+            drop_expired(&mut index, self.retention);
+            index
+                .iter()
+                .rev()
+                .map(|(_, record)| record.clone())
+                .collect()
+        };
+
+        self.file = BufWriter::new(write_compacted(&self.path, &record_list)?);
+
+        Ok(())
+    }
+}
+
+fn drop_expired(index: &mut Index, retention: Duration) {
+    let expired_key_list: Vec<JournalKey> = index
+        .iter()
+        .filter(|(_, record)| record.is_expired(retention))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in expired_key_list {
+        index.pop(&key);
+    }
+}
+
+/// Rewrites the journal at `path` with `record_list`, oldest first, and returns
+/// the rewritten file, opened for appending.
+fn write_compacted(path: &Path, record_list: &[SubmissionRecord]) -> Result<File, io::Error> {
+    let compacted_path = path.with_extension("jsonl.compacted");
+
+    let mut file = BufWriter::new(File::create(&compacted_path)?);
+    for record in record_list {
+        serde_json::to_writer(&mut file, record)?;
+        file.write_all(b"\n")?;
+    }
+    file.into_inner()?.sync_all()?;
+    fs::rename(&compacted_path, path)?;
+
+    OpenOptions::new().append(true).open(path)
+}
+
+fn sender(raw_transaction: &str) -> Option<String> {
+    decode_raw_transaction(raw_transaction, usize::MAX)
+        .ok()
+        .map(|transaction| format!("{:?}", transaction.from))
+}
+
+/// Returns the current unix time in milliseconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use sequencer::types::EthRawBundleTransaction;
+    use serde_json::json;
+
+    use super::*;
+
+    const RETENTION: Duration = Duration::from_secs(60 * 60);
+    const CAPACITY: usize = 16;
+
+    fn journal_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("secure-rpc-{}-{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn record(rollup_id: &str, transaction_hash: &str, submitted_at: u64) -> SubmissionRecord {
+        serde_json::from_value(json!({
+            "transaction_hash": transaction_hash,
+            "rollup_id": rollup_id,
+            "sender": null,
+            "encryption_key_id": null,
+            "sequencer_rpc_url": "http://127.0.0.1:3000",
+            "order_commitment": {
+                "data": {
+                    "rollup_id": rollup_id,
+                    "block_height": 1,
+                    "transaction_order": 0,
+                    "pre_merkle_path": [],
+                },
+                "signature": "0x",
+            },
+            "submitted_at": submitted_at,
+            "committed_at": submitted_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn replays_recorded_submissions() {
+        let path = journal_path("replays-recorded-submissions");
+
+        let journal = SubmissionJournal::open(&path, RETENTION, CAPACITY).unwrap();
+        journal.record(record("rollup-1", "0xAB", now()));
+        journal.record(record("rollup-2", "0xab", now()));
+        assert_eq!(
+            journal.get("rollup-1", "0xab").unwrap().rollup_id,
+            "rollup-1"
+        );
+        assert_eq!(
+            journal.get("rollup-2", "0xAB").unwrap().rollup_id,
+            "rollup-2"
+        );
+        assert!(journal.get("rollup-3", "0xab").is_none());
+
+        // Dropping the journal waits for the writer to append the records.
+        drop(journal);
+
+        let journal = SubmissionJournal::open(&path, RETENTION, CAPACITY).unwrap();
+        assert!(journal.get("rollup-1", "0xab").is_some());
+        assert!(journal.get("rollup-2", "0xab").is_some());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn lists_a_bundle_under_every_hash() {
+        let raw_transaction_list = vec!["0x01".to_owned(), "0x02".to_owned()];
+        let raw_bundle = RawTransaction::EthBundle(EthRawBundleTransaction(
+            serde_json::to_string(&raw_transaction_list).unwrap(),
+        ));
+        let bundle_hash = bundle_hash(&raw_transaction_list).unwrap();
+
+        let record_list = SubmissionRecord::list(
+            &raw_bundle,
+            "rollup-1",
+            None,
+            "http://127.0.0.1:3000",
+            &record("rollup-1", "0x01", now()).order_commitment,
+            now(),
+        );
+
+        assert_eq!(record_list.len(), 3);
+        assert_eq!(record_list[0].transaction_hash, bundle_hash);
+        assert!(record_list
+            .iter()
+            .all(|record| record.bundle_hash.as_ref() == Some(&bundle_hash)));
+    }
+
+    #[test]
+    fn drops_expired_submissions() {
+        let path = journal_path("drops-expired-submissions");
+
+        let journal = SubmissionJournal::open(&path, RETENTION, CAPACITY).unwrap();
+        journal.record(record("rollup-1", "0x01", 0));
+        journal.record(record("rollup-1", "0x02", now()));
+        assert!(journal.get("rollup-1", "0x01").is_none());
+        drop(journal);
+
+        // Opening the journal compacts the expired submission out of the file.
+        SubmissionJournal::open(&path, RETENTION, CAPACITY).unwrap();
+        let journal_string = fs::read_to_string(&path).unwrap();
+        assert_eq!(journal_string.lines().count(), 1);
+        assert!(journal_string.contains("0x02"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_latest_submissions_up_to_the_capacity() {
+        let path = journal_path("keeps-the-latest-submissions");
+
+        let journal = SubmissionJournal::open(&path, RETENTION, 2).unwrap();
+        for transaction_hash in ["0x01", "0x02", "0x03"] {
+            journal.record(record("rollup-1", transaction_hash, now()));
+        }
+        assert!(journal.get("rollup-1", "0x01").is_none());
+        drop(journal);
+
+        let journal = SubmissionJournal::open(&path, RETENTION, 2).unwrap();
+        assert!(journal.get("rollup-1", "0x01").is_none());
+        assert!(journal.get("rollup-1", "0x02").is_some());
+        assert!(journal.get("rollup-1", "0x03").is_some());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn drops_a_torn_last_record() {
+        let path = journal_path("drops-a-torn-last-record");
+        let mut line = serde_json::to_string(&record("rollup-1", "0x01", now())).unwrap();
+        line.push_str("\n{\"transaction_hash\":");
+        fs::write(&path, line).unwrap();
+
+        let journal = SubmissionJournal::open(&path, RETENTION, CAPACITY).unwrap();
+        assert!(journal.get("rollup-1", "0x01").is_some());
+        drop(journal);
+
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn refuses_malformed_records() {
+        let path = journal_path("refuses-malformed-records");
+        let line = serde_json::to_string(&record("rollup-1", "0x01", now())).unwrap();
+        fs::write(&path, format!("{{\"transaction_hash\":\n{}\n", line)).unwrap();
+
+        assert!(matches!(
+            SubmissionJournal::open(&path, RETENTION, CAPACITY),
+            Err(Error::Journal(_))
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
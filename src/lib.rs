@@ -1,5 +1,12 @@
 pub mod client;
 pub mod error;
+pub mod gateway;
+pub mod journal;
+pub mod metrics;
+pub mod monitoring;
+pub mod order_commitment;
 pub mod rpc;
 pub mod state;
+pub mod task;
 pub mod types;
+pub mod validation;
@@ -0,0 +1,233 @@
+use std::{sync::LazyLock, time::Duration};
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Returns the process-wide metrics.
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+pub struct Metrics {
+    registry: Registry,
+    rpc_requests: IntCounterVec,
+    rpc_request_duration: HistogramVec,
+    distributed_key_generation_request_duration: HistogramVec,
+    encryption_duration: HistogramVec,
+    sequencer_requests: IntCounterVec,
+    rollup_request_duration: HistogramVec,
+    rate_limited_requests: IntCounterVec,
+    order_commitments: IntCounterVec,
+    response_cache_requests: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("secure_rpc".into()), None).unwrap();
+
+        let rpc_requests = IntCounterVec::new(
+            Opts::new(
+                "rpc_requests_total",
+                "RPC requests by rollup, method and status",
+            ),
+            &["rollup_id", "method", "status"],
+        )
+        .unwrap();
+        let rpc_request_duration = HistogramVec::new(
+            HistogramOpts::new("rpc_request_duration_seconds", "RPC request latency"),
+            &["rollup_id", "method"],
+        )
+        .unwrap();
+        let distributed_key_generation_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "distributed_key_generation_request_duration_seconds",
+                "Distributed key generation request latency",
+            ),
+            &["rollup_id", "method", "status"],
+        )
+        .unwrap();
+        let encryption_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "encryption_duration_seconds",
+                "Transaction encryption time by rollup and encrypted transaction type",
+            ),
+            &["rollup_id", "encrypted_transaction_type"],
+        )
+        .unwrap();
+        let sequencer_requests = IntCounterVec::new(
+            Opts::new(
+                "sequencer_requests_total",
+                "Sequencer requests by rollup, sequencer and status",
+            ),
+            &["rollup_id", "sequencer", "status"],
+        )
+        .unwrap();
+        let rollup_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "rollup_request_duration_seconds",
+                "Rollup upstream request latency",
+            ),
+            &["rollup_id", "method", "status"],
+        )
+        .unwrap();
+        let rate_limited_requests = IntCounterVec::new(
+            Opts::new(
+                "rate_limited_requests_total",
+                "Requests rejected by the rate limiter by rollup and method class",
+            ),
+            &["rollup_id", "method_class"],
+        )
+        .unwrap();
+        let order_commitments = IntCounterVec::new(
+            Opts::new(
+                "order_commitments_total",
+                "Verified order commitments by rollup and status",
+            ),
+            &["rollup_id", "status"],
+        )
+        .unwrap();
+        let response_cache_requests = IntCounterVec::new(
+            Opts::new(
+                "response_cache_requests_total",
+                "Response cache lookups by rollup, method and status",
+            ),
+            &["rollup_id", "method", "status"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(rpc_requests.clone())).unwrap();
+        registry
+            .register(Box::new(rpc_request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(
+                distributed_key_generation_request_duration.clone(),
+            ))
+            .unwrap();
+        registry
+            .register(Box::new(encryption_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(sequencer_requests.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(rollup_request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(rate_limited_requests.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(order_commitments.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(response_cache_requests.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            rpc_requests,
+            rpc_request_duration,
+            distributed_key_generation_request_duration,
+            encryption_duration,
+            sequencer_requests,
+            rollup_request_duration,
+            rate_limited_requests,
+            order_commitments,
+            response_cache_requests,
+        }
+    }
+
+    /// Encodes every metric in the Prometheus text format.
+    pub fn encode(&self) -> String {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .unwrap_or_default()
+    }
+
+    pub fn observe_rpc_request(
+        &self,
+        rollup_id: &str,
+        method: &str,
+        is_success: bool,
+        duration: Duration,
+    ) {
+        self.rpc_requests
+            .with_label_values(&[rollup_id, method, status(is_success)])
+            .inc();
+        self.rpc_request_duration
+            .with_label_values(&[rollup_id, method])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_distributed_key_generation_request(
+        &self,
+        rollup_id: &str,
+        method: &str,
+        is_success: bool,
+        duration: Duration,
+    ) {
+        self.distributed_key_generation_request_duration
+            .with_label_values(&[rollup_id, method, status(is_success)])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_encryption(
+        &self,
+        rollup_id: &str,
+        encrypted_transaction_type: &str,
+        duration: Duration,
+    ) {
+        self.encryption_duration
+            .with_label_values(&[rollup_id, encrypted_transaction_type])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// `status` is one of `success`, `rejected` or `failure`.
+    pub fn observe_sequencer_request(&self, rollup_id: &str, sequencer: &str, status: &str) {
+        self.sequencer_requests
+            .with_label_values(&[rollup_id, sequencer, status])
+            .inc();
+    }
+
+    pub fn observe_rollup_request(
+        &self,
+        rollup_id: &str,
+        method: &str,
+        is_success: bool,
+        duration: Duration,
+    ) {
+        self.rollup_request_duration
+            .with_label_values(&[rollup_id, method, status(is_success)])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_rate_limited_request(&self, rollup_id: &str, method_class: &str) {
+        self.rate_limited_requests
+            .with_label_values(&[rollup_id, method_class])
+            .inc();
+    }
+
+    /// `status` is either `valid` or `invalid`.
+    pub fn observe_order_commitment(&self, rollup_id: &str, status: &str) {
+        self.order_commitments
+            .with_label_values(&[rollup_id, status])
+            .inc();
+    }
+
+    pub fn observe_response_cache_request(&self, rollup_id: &str, method: &str, is_hit: bool) {
+        let status = if is_hit { "hit" } else { "miss" };
+
+        self.response_cache_requests
+            .with_label_values(&[rollup_id, method, status])
+            .inc();
+    }
+}
+
+fn status(is_success: bool) -> &'static str {
+    if is_success {
+        "success"
+    } else {
+        "failure"
+    }
+}
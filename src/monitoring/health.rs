@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use sequencer::types::EncryptedTransactionType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{rpc::eth, state::AppState};
+
+const ROLLUP_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The readiness of every hosted rollup, ready only if all of them are.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadinessReport {
+    pub is_ready: bool,
+    pub rollup_list: Vec<RollupReadiness>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RollupReadiness {
+    pub rollup_id: String,
+    pub is_ready: bool,
+    pub encryption: DependencyStatus,
+    pub sequencer: DependencyStatus,
+    pub rollup: DependencyStatus,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DependencyStatus {
+    pub is_healthy: bool,
+    pub detail: String,
+    pub last_error: Option<String>,
+}
+
+impl DependencyStatus {
+    fn healthy(detail: impl Into<String>) -> Self {
+        Self {
+            is_healthy: true,
+            detail: detail.into(),
+            last_error: None,
+        }
+    }
+
+    fn unhealthy(detail: impl Into<String>, last_error: Option<String>) -> Self {
+        Self {
+            is_healthy: false,
+            detail: detail.into(),
+            last_error,
+        }
+    }
+}
+
+/// Checks the dependencies of every hosted rollup concurrently.
+pub async fn check_readiness(context_list: &[AppState]) -> ReadinessReport {
+    let rollup_list =
+        futures_util::future::join_all(context_list.iter().map(check_rollup_readiness)).await;
+
+    ReadinessReport {
+        is_ready: rollup_list.iter().all(|rollup| rollup.is_ready),
+        rollup_list,
+    }
+}
+
+/// Checks every dependency a rollup needs to serve transactions.
+pub async fn check_rollup_readiness(context: &AppState) -> RollupReadiness {
+    let encryption = check_encryption(context);
+    let sequencer = check_sequencer(context);
+    let rollup = check_rollup(context).await;
+
+    RollupReadiness {
+        rollup_id: context.config().rollup_id().clone(),
+        is_ready: encryption.is_healthy && sequencer.is_healthy && rollup.is_healthy,
+        encryption,
+        sequencer,
+        rollup,
+    }
+}
+
+fn check_encryption(context: &AppState) -> DependencyStatus {
+    if !context.config().is_using_encryption() {
+        return DependencyStatus::healthy("encryption disabled");
+    }
+
+    match context.config().encrypted_transaction_type() {
+        EncryptedTransactionType::Skde => match context.latest_encryption_key() {
+            Ok(encryption_key) => DependencyStatus::healthy(format!(
+                "key_id {} refreshed {} ms ago",
+                encryption_key.key_id,
+                encryption_key.age().as_millis()
+            )),
+            Err(error) => DependencyStatus::unhealthy(
+                error.kind(),
+                context.encryption_key_cache().last_error(),
+            ),
+        },
+        EncryptedTransactionType::Pvde => {
+            let is_loaded = context
+                .pvde_params()
+                .load()
+                .as_ref()
+                .as_ref()
+                .map(|pvde_params| pvde_params.time_lock_puzzle_param().is_some())
+                .unwrap_or(false);
+
+            if is_loaded {
+                DependencyStatus::healthy("PVDE parameters loaded")
+            } else {
+                DependencyStatus::unhealthy("PVDE parameters not loaded", None)
+            }
+        }
+        _ => DependencyStatus::unhealthy("unsupported encrypted transaction type", None),
+    }
+}
+
+fn check_sequencer(context: &AppState) -> DependencyStatus {
+    let pool_status = context.sequencer_client().pool_status();
+    let healthy_count = pool_status
+        .iter()
+        .filter(|sequencer| sequencer.is_healthy)
+        .count();
+    let detail = format!("{}/{} sequencers healthy", healthy_count, pool_status.len());
+
+    if healthy_count > 0 {
+        DependencyStatus::healthy(detail)
+    } else {
+        let last_error = pool_status
+            .iter()
+            .find_map(|sequencer| sequencer.last_error.clone());
+
+        DependencyStatus::unhealthy(detail, last_error)
+    }
+}
+
+async fn check_rollup(context: &AppState) -> DependencyStatus {
+    // The chain id is cached, so probe the rollup past the response cache.
+    let probe = eth::request::<_, Value>("eth_chainId", Vec::<Value>::new(), context.clone());
+
+    match tokio::time::timeout(ROLLUP_PROBE_TIMEOUT, probe).await {
+        Ok(Ok(chain_id)) => DependencyStatus::healthy(format!("chain id {}", chain_id)),
+        Ok(Err(error)) => DependencyStatus::unhealthy(
+            "rollup rpc url returned an error",
+            Some(format!("{:?}", error)),
+        ),
+        Err(_) => DependencyStatus::unhealthy(
+            "rollup rpc url timed out",
+            Some(format!("no response within {:?}", ROLLUP_PROBE_TIMEOUT)),
+        ),
+    }
+}
@@ -0,0 +1,67 @@
+mod health;
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+pub use health::{
+    check_readiness, check_rollup_readiness, DependencyStatus, ReadinessReport, RollupReadiness,
+};
+use serde_json::json;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{error::Error, metrics::metrics, state::AppState};
+
+/// Serves operational endpoints on a port separate from the gateway:
+///
+/// - `/metrics`: Prometheus metrics.
+/// - `/health/live`: answers as long as the process is serving.
+/// - `/health/ready`: `503` unless the encryption key (or PVDE parameters), at
+///   least one sequencer and the rollup node are available for every rollup in
+///   `context_list`.
+pub async fn serve(
+    context_list: Vec<AppState>,
+    listen_address: impl AsRef<str>,
+) -> Result<JoinHandle<()>, Error> {
+    let router = Router::new()
+        .route("/metrics", get(handle_metrics))
+        .route("/health/live", get(handle_liveness))
+        .route("/health/ready", get(handle_readiness))
+        .with_state(Arc::new(context_list));
+
+    let listener = TcpListener::bind(listen_address.as_ref()).await?;
+
+    Ok(tokio::spawn(async move {
+        if let Err(error) = axum::serve(listener, router).await {
+            tracing::error!("Monitoring server stopped: {:?}", error);
+        }
+    }))
+}
+
+async fn handle_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().encode(),
+    )
+}
+
+async fn handle_liveness() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn handle_readiness(State(context_list): State<Arc<Vec<AppState>>>) -> impl IntoResponse {
+    let readiness_report = check_readiness(&context_list).await;
+
+    let status = if readiness_report.is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(readiness_report))
+}
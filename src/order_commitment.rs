@@ -0,0 +1,191 @@
+use radius_sdk::signature::{Address, ChainType};
+use sequencer::types::{OrderCommitment, RawTransaction};
+
+use crate::{
+    error::Error, journal::SubmissionRecord, metrics::metrics, state::AppState,
+    types::OrderCommitmentVerification,
+};
+
+/// An order commitment returned by the sequencer for a submitted transaction.
+pub struct Submission<'a> {
+    pub raw_transaction: &'a RawTransaction,
+    pub rollup_id: &'a str,
+    pub encryption_key_id: Option<u64>,
+    pub sequencer_rpc_url: String,
+    pub order_commitment: OrderCommitment,
+    /// Unix time in milliseconds when the submission was received.
+    pub submitted_at: u64,
+}
+
+/// Verifies the order commitment of `submission` according to
+/// `order_commitment_verification`, journals it and returns it.
+///
+/// In `reject` mode an invalid order commitment is journaled and answered
+/// with [`Error::InvalidOrderCommitment`].
+pub fn accept_order_commitment(
+    context: &AppState,
+    submission: Submission,
+) -> Result<OrderCommitment, Error> {
+    let verification = context.config().order_commitment_verification();
+
+    let verification_error = match verification {
+        OrderCommitmentVerification::Disabled => None,
+        _ => verify_order_commitment(
+            context.config().sequencer_address_list(),
+            submission.rollup_id,
+            &submission.order_commitment,
+        )
+        .err(),
+    };
+
+    if verification != OrderCommitmentVerification::Disabled {
+        let status = if verification_error.is_some() {
+            "invalid"
+        } else {
+            "valid"
+        };
+        metrics().observe_order_commitment(context.config().rollup_id(), status);
+    }
+
+    if let Some(error) = &verification_error {
+        tracing::warn!(
+            "Invalid order commitment from {}: {:?} - order_commitment: {:?}",
+            submission.sequencer_rpc_url,
+            error,
+            submission.order_commitment
+        );
+    }
+
+    for mut record in SubmissionRecord::list(
+        submission.raw_transaction,
+        submission.rollup_id,
+        submission.encryption_key_id,
+        &submission.sequencer_rpc_url,
+        &submission.order_commitment,
+        submission.submitted_at,
+    ) {
+        if verification != OrderCommitmentVerification::Disabled {
+            record.is_order_commitment_valid = Some(verification_error.is_none());
+        }
+        context.submission_journal().record(record);
+    }
+
+    match verification_error {
+        Some(error) if verification == OrderCommitmentVerification::Reject => Err(error),
+        _ => Ok(submission.order_commitment),
+    }
+}
+
+/// Checks that the order commitment is for the rollup of the submission and
+/// is signed by one of the cluster sequencers.
+///
+/// The commitment data of the sequencer carries no transaction hash, so the
+/// signature over the rollup id, block height and order is what binds the
+/// sequencer to the order it returned.
+pub fn verify_order_commitment(
+    sequencer_address_list: &[String],
+    rollup_id: &str,
+    order_commitment: &OrderCommitment,
+) -> Result<(), Error> {
+    let OrderCommitment::Single(order_commitment) = order_commitment else {
+        return Err(Error::InvalidOrderCommitment(
+            "unsupported order commitment type".into(),
+        ));
+    };
+
+    if order_commitment.data.rollup_id != rollup_id {
+        return Err(Error::InvalidOrderCommitment(format!(
+            "committed to rollup {} instead of {}",
+            order_commitment.data.rollup_id, rollup_id
+        )));
+    }
+
+    let is_signed_by_sequencer = sequencer_address_list.iter().any(|sequencer_address| {
+        Address::from_str(ChainType::Ethereum, sequencer_address)
+            .and_then(|address| {
+                order_commitment.signature.verify_message(
+                    ChainType::Ethereum,
+                    &order_commitment.data,
+                    address,
+                )
+            })
+            .is_ok()
+    });
+
+    if !is_signed_by_sequencer {
+        return Err(Error::InvalidOrderCommitment(
+            "not signed by a cluster sequencer".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use radius_sdk::signature::PrivateKeySigner;
+    use sequencer::types::{OrderCommitmentData, SingleOrderCommitment};
+
+    use super::*;
+
+    const SEQUENCER_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const SEQUENCER_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const OTHER_PRIVATE_KEY: &str =
+        "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn order_commitment(private_key: &str, rollup_id: &str) -> OrderCommitment {
+        let data = OrderCommitmentData {
+            rollup_id: rollup_id.to_owned(),
+            block_height: 1,
+            transaction_order: 0,
+            pre_merkle_path: Vec::new(),
+        };
+        let signature = PrivateKeySigner::from_str(ChainType::Ethereum, private_key)
+            .unwrap()
+            .sign_message(&data)
+            .unwrap();
+
+        OrderCommitment::Single(SingleOrderCommitment { data, signature })
+    }
+
+    #[test]
+    fn accepts_a_commitment_signed_by_a_sequencer() {
+        let sequencer_address_list = vec![SEQUENCER_ADDRESS.to_owned()];
+
+        assert!(verify_order_commitment(
+            &sequencer_address_list,
+            "rollup-1",
+            &order_commitment(SEQUENCER_PRIVATE_KEY, "rollup-1"),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_a_signer_outside_the_cluster() {
+        let sequencer_address_list = vec![SEQUENCER_ADDRESS.to_owned()];
+
+        assert!(matches!(
+            verify_order_commitment(
+                &sequencer_address_list,
+                "rollup-1",
+                &order_commitment(OTHER_PRIVATE_KEY, "rollup-1"),
+            ),
+            Err(Error::InvalidOrderCommitment(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_commitment_for_another_rollup() {
+        let sequencer_address_list = vec![SEQUENCER_ADDRESS.to_owned()];
+
+        assert!(matches!(
+            verify_order_commitment(
+                &sequencer_address_list,
+                "rollup-1",
+                &order_commitment(SEQUENCER_PRIVATE_KEY, "rollup-2"),
+            ),
+            Err(Error::InvalidOrderCommitment(_))
+        ));
+    }
+}
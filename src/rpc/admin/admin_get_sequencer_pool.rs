@@ -0,0 +1,42 @@
+use serde_json::Value;
+
+use crate::{client::sequencer::SequencerHealth, rpc::prelude::*};
+
+/// `admin_get_sequencer_pool()` returns the health of every sequencer.
+///
+/// Sequencer rpc urls are internal topology, so this is only served on the
+/// internal RPC server and never through the gateway. Any params, including
+/// `null` and none at all, are ignored.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdminGetSequencerPool(Value);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdminGetSequencerPoolResponse {
+    pub sequencer_list: Vec<SequencerHealth>,
+}
+
+impl RpcMethod for AdminGetSequencerPool {
+    type Response = AdminGetSequencerPoolResponse;
+
+    fn method() -> &'static str {
+        "admin_get_sequencer_pool"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        Ok(AdminGetSequencerPoolResponse {
+            sequencer_list: context.sequencer_client().pool_status(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_params() {
+        for params in ["null", "[]", "{}"] {
+            assert!(serde_json::from_str::<AdminGetSequencerPool>(params).is_ok());
+        }
+    }
+}
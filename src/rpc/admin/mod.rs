@@ -0,0 +1,3 @@
+mod admin_get_sequencer_pool;
+
+pub use admin_get_sequencer_pool::AdminGetSequencerPool;
@@ -1,4 +1,5 @@
-#[allow(unused)]
+use std::str::FromStr;
+
 use pvde::{
     encryption::{
         poseidon_encryption,
@@ -15,7 +16,7 @@ use pvde::{
     },
 };
 
-use crate::rpc::prelude::*;
+use crate::{rpc::prelude::*, state::PvdeParams};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DecryptTransaction {
@@ -27,136 +28,97 @@ pub struct DecryptTransactionResponse {
     pub raw_transaction: RawTransaction,
 }
 
-impl RpcParameter<AppState> for DecryptTransaction {
+impl RpcMethod for DecryptTransaction {
     type Response = DecryptTransactionResponse;
 
     fn method() -> &'static str {
         "decrypt_transaction"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         let transaction_data = self.encrypted_transaction.transaction_data();
-        // let encrypted_data = transaction_data.encrypted_data();
+        let encrypted_data = transaction_data.encrypted_data();
 
         let decrypted_data = match &self.encrypted_transaction {
-            // EncryptedTransaction::Pvde(_pvde_encrypted_transaction) => {
-            // match context.config().is_using_zkp() {
-            //     true => {
-            //         let pvde_params = context.pvde_params().load().as_ref().clone().unwrap();
-
-            //         let key_validation_zkp_param = pvde_params
-            //             .key_validation_zkp_param()
-            //             .as_ref()
-            //             .unwrap()
-            //             .clone();
-            //         let key_validation_verify_key = pvde_params
-            //             .key_validation_verifying_key()
-            //             .as_ref()
-            //             .unwrap()
-            //             .clone();
-
-            //         let poseidon_encryption_zkp_param = pvde_params
-            //             .poseidon_encryption_zkp_param()
-            //             .as_ref()
-            //             .unwrap()
-            //             .clone();
-
-            //         let poseidon_encryption_verify_key = pvde_params
-            //             .poseidon_encryption_verifying_key()
-            //             .as_ref()
-            //             .unwrap()
-            //             .clone();
-
-            //         let time_lock_puzzle_param = pvde_params
-            //             .time_lock_puzzle_param()
-            //             .as_ref()
-            //             .unwrap()
-            //             .clone();
-
-            //         let pvde_zkp = pvde_encrypted_transaction.pvde_zkp().unwrap();
-
-            //         let sigma_protocol_public_input =
-            //             pvde_zkp.public_input().to_sigma_protocol_public_input();
-
-            //         let sigma_protocol_param = SigmaProtocolParam {
-            //             n: time_lock_puzzle_param.n.clone(),
-            //             g: time_lock_puzzle_param.g.clone(),
-            //             y_two: time_lock_puzzle_param.y_two.clone(),
-            //         };
-            //         let is_valid = verify_sigma_protocol(
-            //             &sigma_protocol_public_input,
-            //             &sigma_protocol_param,
-            //         );
-
-            //         if !is_valid {
-            //             return Err(RpcError::from(Error::PvdeZkpInvalid));
-            //         }
-            //         // log::info!("Done verify_sigma_protocol: {:?}", is_valid);
-
-            //         let key_validation_public_input =
-            //             pvde_zkp.public_input().to_key_validation_public_input();
-            //         // let key_validation_public_input = KeyValidationPublicInput {
-            //         //     k_two: pvde_zkp.public_input.k_two.clone(),
-            //         //     k_hash_value: pvde_zkp.public_input.k_hash_value.clone(),
-            //         // };
-            //         let is_valid = verify_key_validation(
-            //             &key_validation_zkp_param,
-            //             &key_validation_verify_key,
-            //             &key_validation_public_input,
-            //             &pvde_zkp.time_lock_puzzle_proof().clone().into_inner(),
-            //         );
-
-            //         if !is_valid {
-            //             return Err(RpcError::from(Error::PvdeZkpInvalid));
-            //         }
-            //         // log::info!("Done verify_key_validation: {:?}", is_valid);
-
-            //         let poseidon_encryption_public_input = PoseidonEncryptionPublicInput {
-            //             encrypted_data: encrypted_data.clone().into_inner(),
-            //             k_hash_value: pvde_zkp.public_input().k_hash_value().clone(),
-            //         };
-            //         let is_valid = verify_poseidon_encryption(
-            //             &poseidon_encryption_zkp_param,
-            //             &poseidon_encryption_verify_key,
-            //             &poseidon_encryption_public_input,
-            //             &pvde_zkp.encryption_proof().clone().into_inner(),
-            //         );
-
-            //         if !is_valid {
-            //             return Err(RpcError::from(Error::PvdeZkpInvalid));
-            //         }
-            //         log::info!("Done verify_poseidon_encryption: {:?}", is_valid);
-            //     }
-            //     false => {}
-            // }
-
-            // let time_lock_puzzle = pvde_encrypted_transaction.time_lock_puzzle();
-
-            // let o = BigUint::from_str(time_lock_puzzle.o()).unwrap();
-            // let t = time_lock_puzzle.t();
-            // let n = BigUint::from_str(time_lock_puzzle.n()).unwrap();
-            // let solved_k = solve_time_lock_puzzle(o, t, n);
-            // let solved_k_hash_value = hash::hash(solved_k.clone());
-
-            // let decrypted_data = poseidon_encryption::decrypt(
-            //     encrypted_data.clone().into_inner().as_str(),
-            //     &solved_k_hash_value,
-            // );
-
-            // decrypted_data
-            //     return Err(Error::UnsupportedDecryptionType.into());
-            // }
+            EncryptedTransaction::Pvde(pvde_encrypted_transaction) => {
+                let pvde_params = context
+                    .pvde_params()
+                    .load()
+                    .as_ref()
+                    .clone()
+                    .ok_or(Error::PvdeParamsUnavailable)?;
+
+                let pvde_zkp = if context.config().is_using_zkp() {
+                    let pvde_zkp = pvde_encrypted_transaction
+                        .pvde_zkp()
+                        .ok_or(Error::PvdeZkpInvalid)?;
+
+                    verify_pvde_zkp(&pvde_params, pvde_zkp, encrypted_data)?;
+                    Some(pvde_zkp)
+                } else {
+                    None
+                };
+
+                let time_lock_puzzle = pvde_encrypted_transaction.time_lock_puzzle();
+
+                let o = BigUint::from_str(time_lock_puzzle.o()).map_err(|error| {
+                    tracing::error!("Failed to parse time lock puzzle (o): {:?}", error);
+                    Error::DecodeFailed
+                })?;
+                let t = time_lock_puzzle.t();
+                let n = BigUint::from_str(time_lock_puzzle.n()).map_err(|error| {
+                    tracing::error!("Failed to parse time lock puzzle (n): {:?}", error);
+                    Error::DecodeFailed
+                })?;
+
+                // The puzzle takes `t` sequential squarings to solve, so only
+                // puzzles made with our own parameters are worth solving.
+                let time_lock_puzzle_param = pvde_params
+                    .time_lock_puzzle_param()
+                    .as_ref()
+                    .ok_or(Error::PvdeParamsUnavailable)?;
+                if t != time_lock_puzzle_param.t || n != time_lock_puzzle_param.n {
+                    tracing::warn!("Time lock puzzle does not match the PVDE parameters");
+                    return Err(Error::TimeLockPuzzleMismatch);
+                }
+
+                if let Some(pvde_zkp) = pvde_zkp {
+                    verify_time_lock_puzzle_binding(pvde_zkp, &o)?;
+                }
+
+                tracing::info!("Solve PVDE time lock puzzle");
+
+                let k = tokio::task::spawn_blocking(move || solve_time_lock_puzzle(o, t, n))
+                    .await
+                    .map_err(|error| Error::Syscall(std::io::Error::other(error)))?;
+
+                if let Some(pvde_zkp) = pvde_zkp {
+                    verify_key_binding(pvde_zkp, k.clone())?;
+                }
+                let solved_k_hash_value = hash::hash(k);
+
+                poseidon_encryption::decrypt(
+                    encrypted_data.clone().into_inner().as_str(),
+                    &solved_k_hash_value,
+                )
+            }
             EncryptedTransaction::Skde(skde_encrypted_transaction) => {
-                let distributed_key_generation_client =
-                    context.distributed_key_generation_client().clone().unwrap();
+                let distributed_key_generation_client = context
+                    .distributed_key_generation_client()
+                    .clone()
+                    .ok_or(Error::Uninitialized)?;
 
                 let get_decryption_key_response = distributed_key_generation_client
                     .get_decryption_key(skde_encrypted_transaction.key_id)
-                    .await?;
+                    .await
+                    .map_err(Error::DistributedKeyGenerationClient)?;
 
-                let skde_params = context.skde_params();
+                let skde_params = context
+                    .skde_params()
+                    .as_ref()
+                    .ok_or(Error::FailedToGetSkdeParams)?;
 
-                let encrypted_data = transaction_data.encrypted_data().clone().into_inner();
+                let encrypted_data = encrypted_data.clone().into_inner();
 
                 tracing::info!("Decrypt SKDE encrypted data");
 
@@ -171,7 +133,7 @@ impl RpcParameter<AppState> for DecryptTransaction {
 
         match transaction_data {
             TransactionData::Eth(eth_transaction_data) => {
-                let eth_plain_data: EthPlainData = serde_json::from_str(&decrypted_data).unwrap();
+                let eth_plain_data: EthPlainData = serde_json::from_str(&decrypted_data)?;
 
                 let rollup_transaction = eth_transaction_data
                     .open_data
@@ -182,7 +144,140 @@ impl RpcParameter<AppState> for DecryptTransaction {
 
                 Ok(DecryptTransactionResponse { raw_transaction })
             }
-            _ => Err(Error::UnsupportedTransactionType.into()),
+            _ => Err(Error::UnsupportedTransactionType),
         }
     }
 }
+
+/// Verifies the sigma protocol, key validation and Poseidon encryption proofs
+/// attached to a PVDE encrypted transaction.
+pub fn verify_pvde_zkp(
+    pvde_params: &PvdeParams,
+    pvde_zkp: &PvdeZkp,
+    encrypted_data: &EncryptedData,
+) -> Result<(), Error> {
+    let time_lock_puzzle_param = pvde_params
+        .time_lock_puzzle_param()
+        .as_ref()
+        .ok_or(Error::PvdeParamsUnavailable)?;
+    let key_validation_zkp_param = pvde_params
+        .key_validation_zkp_param()
+        .as_ref()
+        .ok_or(Error::PvdeParamsUnavailable)?;
+    let key_validation_verifying_key = pvde_params
+        .key_validation_verifying_key()
+        .as_ref()
+        .ok_or(Error::PvdeParamsUnavailable)?;
+    let poseidon_encryption_zkp_param = pvde_params
+        .poseidon_encryption_zkp_param()
+        .as_ref()
+        .ok_or(Error::PvdeParamsUnavailable)?;
+    let poseidon_encryption_verifying_key = pvde_params
+        .poseidon_encryption_verifying_key()
+        .as_ref()
+        .ok_or(Error::PvdeParamsUnavailable)?;
+
+    let sigma_protocol_public_input = pvde_zkp.public_input().to_sigma_protocol_public_input();
+    let sigma_protocol_param = SigmaProtocolParam {
+        n: time_lock_puzzle_param.n.clone(),
+        g: time_lock_puzzle_param.g.clone(),
+        y_two: time_lock_puzzle_param.y_two.clone(),
+    };
+    if !verify_sigma_protocol(&sigma_protocol_public_input, &sigma_protocol_param) {
+        tracing::warn!("Invalid PVDE sigma protocol proof");
+        return Err(Error::PvdeZkpInvalid);
+    }
+
+    let key_validation_public_input = pvde_zkp.public_input().to_key_validation_public_input();
+    if !verify_key_validation(
+        key_validation_zkp_param,
+        key_validation_verifying_key,
+        &key_validation_public_input,
+        &pvde_zkp.time_lock_puzzle_proof().clone().into_inner(),
+    ) {
+        tracing::warn!("Invalid PVDE key validation proof");
+        return Err(Error::PvdeZkpInvalid);
+    }
+
+    let poseidon_encryption_public_input = PoseidonEncryptionPublicInput {
+        encrypted_data: encrypted_data.clone().into_inner(),
+        k_hash_value: pvde_zkp.public_input().k_hash_value().clone(),
+    };
+    if !verify_poseidon_encryption(
+        poseidon_encryption_zkp_param,
+        poseidon_encryption_verifying_key,
+        &poseidon_encryption_public_input,
+        &pvde_zkp.encryption_proof().clone().into_inner(),
+    ) {
+        tracing::warn!("Invalid PVDE Poseidon encryption proof");
+        return Err(Error::PvdeZkpInvalid);
+    }
+
+    Ok(())
+}
+
+/// Checks that the proofs were made for the time lock puzzle being solved, so
+/// that valid proofs of another transaction cannot be attached to it.
+pub fn verify_time_lock_puzzle_binding(pvde_zkp: &PvdeZkp, o: &BigUint) -> Result<(), Error> {
+    if pvde_zkp.public_input().to_sigma_protocol_public_input().o != *o {
+        tracing::warn!("PVDE proofs were made for another time lock puzzle");
+        return Err(Error::PvdeZkpInvalid);
+    }
+
+    Ok(())
+}
+
+/// Checks that the key `k` of the solved time lock puzzle hashes to the key
+/// hash the encryption proof was made with.
+pub fn verify_key_binding(pvde_zkp: &PvdeZkp, k: BigUint) -> Result<(), Error> {
+    if hash::hash(k) != *pvde_zkp.public_input().k_hash_value() {
+        tracing::warn!("PVDE proofs were made for another key");
+        return Err(Error::PvdeZkpInvalid);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pvde_zkp(o: u32, k: u32) -> PvdeZkp {
+        let public_input = PvdePublicInput::new(
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            BigUint::from(o),
+            BigUint::from(1u32),
+            hash::hash(BigUint::from(k)),
+        );
+
+        PvdeZkp::new(
+            public_input,
+            TimeLockPuzzleProof::new(Vec::new()),
+            EncryptionProof::new(Vec::new()),
+        )
+    }
+
+    #[test]
+    fn accepts_proofs_of_the_solved_time_lock_puzzle() {
+        let pvde_zkp = pvde_zkp(3, 5);
+
+        assert!(verify_time_lock_puzzle_binding(&pvde_zkp, &BigUint::from(3u32)).is_ok());
+        assert!(verify_key_binding(&pvde_zkp, BigUint::from(5u32)).is_ok());
+    }
+
+    #[test]
+    fn rejects_proofs_of_another_time_lock_puzzle() {
+        let pvde_zkp = pvde_zkp(3, 5);
+
+        assert!(matches!(
+            verify_time_lock_puzzle_binding(&pvde_zkp, &BigUint::from(4u32)),
+            Err(Error::PvdeZkpInvalid)
+        ));
+        assert!(matches!(
+            verify_key_binding(&pvde_zkp, BigUint::from(6u32)),
+            Err(Error::PvdeZkpInvalid)
+        ));
+    }
+}
@@ -1,4 +1,5 @@
-#![allow(unused)]
+use std::time::Instant;
+
 use pvde::{
     encryption::{
         poseidon_encryption_zkp::{
@@ -19,7 +20,6 @@ use pvde::{
             prove as prove_key_validation, KeyValidationParam, KeyValidationPublicInput,
             KeyValidationSecretInput,
         },
-        setup as setup_time_lock_puzzle_param,
         sigma_protocol::{
             generate_sigma_protocol_public_input, SigmaProtocolParam, SigmaProtocolPublicInput,
         },
@@ -29,7 +29,7 @@ use pvde::{
 use rand::{thread_rng, Rng};
 use skde::delay_encryption::SkdeParams;
 
-use crate::rpc::prelude::*;
+use crate::{metrics::metrics, rpc::prelude::*, validation::validate_raw_transaction};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EncryptTransaction {
@@ -41,111 +41,140 @@ pub struct EncryptTransactionResponse {
     pub encrypted_transaction: EncryptedTransaction,
 }
 
-impl RpcParameter<AppState> for EncryptTransaction {
+impl RpcMethod for EncryptTransaction {
     type Response = EncryptTransactionResponse;
 
     fn method() -> &'static str {
         "encrypt_transaction"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         tracing::info!(
             "Encrypt transaction - raw transaction: {:?}",
             self.raw_transaction
         );
 
-        // Convert raw transaction to string
+        // The sequencer decrypts every encrypted transaction into a single
+        // transaction, so bundles are only submitted in plain.
         let raw_transaction_string: String = match &self.raw_transaction {
             RawTransaction::Eth(raw_transaction) => {
                 serde_json::from_str(&serde_json::to_string(&raw_transaction).unwrap())?
             }
-            RawTransaction::EthBundle(raw_transaction) => {
-                serde_json::from_str(&serde_json::to_string(&raw_transaction).unwrap())?
-            }
+            RawTransaction::EthBundle(_) => return Err(Error::EncryptedBundleUnsupported),
         };
 
+        validate_raw_transaction(&context, &raw_transaction_string).await?;
+
         let encrypted_transaction_type = context.config().encrypted_transaction_type();
 
         match encrypted_transaction_type {
             EncryptedTransactionType::Pvde => {
-                // // Generate time lock puzzle
-                // let time_lock_puzzle_param = setup_time_lock_puzzle_param(2048);
-                // let (
-                //     sigma_protocol_public_input,
-                //     key_validation_param,
-                //     key_validation_public_input,
-                //     key_validation_secret_input,
-                // ) = generate_time_lock_puzzle(time_lock_puzzle_param.clone())?;
-
-                // let time_lock_puzzle = TimeLockPuzzle::new(
-                //     time_lock_puzzle_param.t,
-                //     sigma_protocol_public_input.o.to_string(),
-                //     time_lock_puzzle_param.n.to_string(),
-                // );
-
-                // let encrypted_transaction = if context.config().is_using_zkp() {
-                //     let pvde_params = context.pvde_params().load().as_ref().clone().unwrap();
-                //     let key_validation_zkp_param =
-                //         pvde_params.key_validation_zkp_param().clone().unwrap();
-                //     let key_validation_proving_key =
-                //         pvde_params.key_validation_proving_key().clone().unwrap();
-
-                //     let poseidon_encryption_zkp_param =
-                //         pvde_params.poseidon_encryption_zkp_param().clone().unwrap();
-                //     let poseidon_encryption_proving_key = pvde_params
-                //         .poseidon_encryption_proving_key()
-                //         .clone()
-                //         .unwrap();
-
-                //     let pvde_encrypted_transaction = pvde_encrypt_tx_with_zkp(
-                //         &raw_transaction_string,
-                //         &sigma_protocol_public_input,
-                //         &key_validation_param,
-                //         &key_validation_public_input,
-                //         &key_validation_secret_input,
-                //         &key_validation_zkp_param,
-                //         &key_validation_proving_key,
-                //         &poseidon_encryption_zkp_param,
-                //         &poseidon_encryption_proving_key,
-                //         &time_lock_puzzle,
-                //     )?;
-
-                //     EncryptedTransaction::Pvde(pvde_encrypted_transaction)
-                // } else {
-                //     let pvde_encrypted_transaction = pvde_encrypt_transaction(
-                //         &raw_transaction_string,
-                //         &key_validation_secret_input.k,
-                //         &time_lock_puzzle,
-                //     )
-                //     .map_err(|error| {
-                //         tracing::error!("encrypt_tx error: {:?}", error);
-                //         RpcError::from(error)
-                //     })?;
-
-                //     EncryptedTransaction::Pvde(pvde_encrypted_transaction)
-                // };
-
-                // Ok(EncryptTransactionResponse {
-                //     encrypted_transaction,
-                // })
-                Err(Error::UnsupportedEncryptionType.into())
+                let pvde_params = context
+                    .pvde_params()
+                    .load()
+                    .as_ref()
+                    .clone()
+                    .ok_or(Error::PvdeParamsUnavailable)?;
+                let time_lock_puzzle_param = pvde_params
+                    .time_lock_puzzle_param()
+                    .clone()
+                    .ok_or(Error::PvdeParamsUnavailable)?;
+
+                let started_at = Instant::now();
+
+                // Generate time lock puzzle
+                let (
+                    sigma_protocol_public_input,
+                    key_validation_param,
+                    key_validation_public_input,
+                    key_validation_secret_input,
+                ) = generate_time_lock_puzzle(time_lock_puzzle_param.clone())?;
+
+                let time_lock_puzzle = TimeLockPuzzle::new(
+                    time_lock_puzzle_param.t,
+                    sigma_protocol_public_input.o.to_string(),
+                    time_lock_puzzle_param.n.to_string(),
+                );
+
+                let pvde_encrypted_transaction = if context.config().is_using_zkp() {
+                    // Proving takes seconds, so it runs off the async workers.
+                    let raw_transaction_string = raw_transaction_string.clone();
+                    let time_lock_puzzle = time_lock_puzzle.clone();
+
+                    tokio::task::spawn_blocking(move || {
+                        let key_validation_zkp_param = pvde_params
+                            .key_validation_zkp_param()
+                            .as_ref()
+                            .ok_or(Error::PvdeParamsUnavailable)?;
+                        let key_validation_proving_key = pvde_params
+                            .key_validation_proving_key()
+                            .as_ref()
+                            .ok_or(Error::PvdeParamsUnavailable)?;
+                        let poseidon_encryption_zkp_param = pvde_params
+                            .poseidon_encryption_zkp_param()
+                            .as_ref()
+                            .ok_or(Error::PvdeParamsUnavailable)?;
+                        let poseidon_encryption_proving_key = pvde_params
+                            .poseidon_encryption_proving_key()
+                            .as_ref()
+                            .ok_or(Error::PvdeParamsUnavailable)?;
+
+                        pvde_encrypt_tx_with_zkp(
+                            &raw_transaction_string,
+                            &sigma_protocol_public_input,
+                            &key_validation_param,
+                            &key_validation_public_input,
+                            &key_validation_secret_input,
+                            key_validation_zkp_param,
+                            key_validation_proving_key,
+                            poseidon_encryption_zkp_param,
+                            poseidon_encryption_proving_key,
+                            &time_lock_puzzle,
+                        )
+                    })
+                    .await
+                    .map_err(|error| Error::Syscall(std::io::Error::other(error)))??
+                } else {
+                    pvde_encrypt_transaction(
+                        &raw_transaction_string,
+                        &key_validation_secret_input.k,
+                        &time_lock_puzzle,
+                    )?
+                };
+
+                metrics().observe_encryption(
+                    context.config().rollup_id(),
+                    "pvde",
+                    started_at.elapsed(),
+                );
+
+                Ok(EncryptTransactionResponse {
+                    encrypted_transaction: EncryptedTransaction::Pvde(pvde_encrypted_transaction),
+                })
             }
             EncryptedTransactionType::Skde => {
-                let distributed_key_generation_client =
-                    context.distributed_key_generation_client().clone().unwrap();
-                let skde_params = context.skde_params();
+                let skde_params = context
+                    .skde_params()
+                    .as_ref()
+                    .ok_or(Error::FailedToGetSkdeParams)?;
+
+                let encryption_key = context.latest_encryption_key()?;
 
-                let get_latest_encryption_key_response = distributed_key_generation_client
-                    .get_latest_encryption_key()
-                    .await?;
+                let started_at = Instant::now();
 
                 let encrypted_transaction = skde_encrypt_transaction(
                     skde_params,
                     &raw_transaction_string,
-                    &get_latest_encryption_key_response.key_id,
-                    &get_latest_encryption_key_response.encryption_key,
+                    &encryption_key.key_id,
+                    &encryption_key.encryption_key,
                 )?;
 
+                metrics().observe_encryption(
+                    context.config().rollup_id(),
+                    "skde",
+                    started_at.elapsed(),
+                );
+
                 Ok(EncryptTransactionResponse {
                     encrypted_transaction: EncryptedTransaction::Skde(encrypted_transaction),
                 })
@@ -233,88 +262,88 @@ pub fn skde_encrypt_transaction(
     Ok(SkdeEncryptedTransaction::new(transaction_data, *key_id))
 }
 
-// pub fn pvde_encrypt_transaction(
-//     raw_tx: &str,
-//     k: &BigUint,
-//     time_lock_puzzle: &TimeLockPuzzle,
-// ) -> Result<PvdeEncryptedTransaction, Error> {
-//     let (open_data, to_encrypt_data) = get_open_and_encrypted_data(raw_tx)?;
-
-//     let encryption_key = hash::hash(k.clone());
-
-//     let encrypted_data = poseidon_encryption::encrypt(&to_encrypt_data, &encryption_key);
-//     let encrypted_data = EncryptedData::from(encrypted_data);
-//     let transaction_data = TransactionData::Eth(EthTransactionData::new(encrypted_data, open_data));
-
-//     Ok(PvdeEncryptedTransaction::new(
-//         transaction_data,
-//         time_lock_puzzle.clone(),
-//         None,
-//     ))
-// }
-
-// #[allow(clippy::too_many_arguments)]
-// pub fn pvde_encrypt_tx_with_zkp(
-//     raw_tx: &str,
-
-//     sigma_protocol_public_input: &SigmaProtocolPublicInput,
-//     key_validation_param: &KeyValidationParam,
-//     key_validation_public_input: &KeyValidationPublicInput,
-//     key_validation_secret_input: &KeyValidationSecretInput,
-
-//     key_validation_zkp_param: &ParamsKZG<Bn256>,
-//     key_validation_proving_key: &ProvingKey<G1Affine>,
-//     poseidon_encryption_zkp_param: &ParamsKZG<Bn256>,
-//     poseidon_encryption_proving_key: &ProvingKey<G1Affine>,
-//     time_lock_puzzle: &TimeLockPuzzle,
-// ) -> Result<PvdeEncryptedTransaction, Error> {
-//     let (_open_data, to_encrypt_data) = get_open_and_encrypted_data(raw_tx)?;
-//     let mut pvde_encrypted_transaction =
-//         pvde_encrypt_transaction(raw_tx, &key_validation_secret_input.k, time_lock_puzzle)?;
-
-//     // Generate key validation zkp
-//     let proof_of_key_validation = prove_key_validation(
-//         key_validation_zkp_param,
-//         key_validation_proving_key,
-//         key_validation_param,
-//         key_validation_public_input,
-//         key_validation_secret_input,
-//     );
-
-//     // Generate position encryption public & secret input
-//     let poseidon_encryption_public_input = PoseidonEncryptionPublicInput {
-//         encrypted_data: pvde_encrypted_transaction
-//             .transaction_data()
-//             .encrypted_data()
-//             .clone()
-//             .into_inner()
-//             .clone(),
-//         k_hash_value: key_validation_public_input.k_hash_value.clone(),
-//     };
-//     let poseidon_encryption_secret_input = PoseidonEncryptionSecretInput {
-//         data: to_encrypt_data.clone(),
-//         k: key_validation_secret_input.k.clone(),
-//     };
-//     let proof_of_poseidon_encryption = prove_poseidon_encryption(
-//         poseidon_encryption_zkp_param,
-//         poseidon_encryption_proving_key,
-//         &poseidon_encryption_public_input,
-//         &poseidon_encryption_secret_input,
-//     );
-
-//     let public_input = PvdePublicInput::new(
-//         sigma_protocol_public_input.r1.clone(),
-//         sigma_protocol_public_input.r2.clone(),
-//         sigma_protocol_public_input.z.clone(),
-//         sigma_protocol_public_input.o.clone(),
-//         key_validation_public_input.k_two.clone(),
-//         key_validation_public_input.k_hash_value.clone(),
-//     );
-//     let time_lock_puzzle_proof = TimeLockPuzzleProof::new(proof_of_key_validation);
-//     let encryption_proof = EncryptionProof::new(proof_of_poseidon_encryption);
-
-//     let pvde_zkp = PvdeZkp::new(public_input, time_lock_puzzle_proof, encryption_proof);
-//     pvde_encrypted_transaction.set_pvde_zkp(pvde_zkp);
-
-//     Ok(pvde_encrypted_transaction)
-// }
+pub fn pvde_encrypt_transaction(
+    raw_tx: &str,
+    k: &BigUint,
+    time_lock_puzzle: &TimeLockPuzzle,
+) -> Result<PvdeEncryptedTransaction, Error> {
+    let (open_data, to_encrypt_data) = get_open_and_encrypted_data(raw_tx)?;
+
+    let encryption_key = hash::hash(k.clone());
+
+    let encrypted_data = poseidon_encryption::encrypt(&to_encrypt_data, &encryption_key);
+    let encrypted_data = EncryptedData::from(encrypted_data);
+    let transaction_data = TransactionData::Eth(EthTransactionData::new(encrypted_data, open_data));
+
+    Ok(PvdeEncryptedTransaction::new(
+        transaction_data,
+        time_lock_puzzle.clone(),
+        None,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn pvde_encrypt_tx_with_zkp(
+    raw_tx: &str,
+
+    sigma_protocol_public_input: &SigmaProtocolPublicInput,
+    key_validation_param: &KeyValidationParam,
+    key_validation_public_input: &KeyValidationPublicInput,
+    key_validation_secret_input: &KeyValidationSecretInput,
+
+    key_validation_zkp_param: &ParamsKZG<Bn256>,
+    key_validation_proving_key: &ProvingKey<G1Affine>,
+    poseidon_encryption_zkp_param: &ParamsKZG<Bn256>,
+    poseidon_encryption_proving_key: &ProvingKey<G1Affine>,
+    time_lock_puzzle: &TimeLockPuzzle,
+) -> Result<PvdeEncryptedTransaction, Error> {
+    let (_open_data, to_encrypt_data) = get_open_and_encrypted_data(raw_tx)?;
+    let mut pvde_encrypted_transaction =
+        pvde_encrypt_transaction(raw_tx, &key_validation_secret_input.k, time_lock_puzzle)?;
+
+    // Generate key validation zkp
+    let proof_of_key_validation = prove_key_validation(
+        key_validation_zkp_param,
+        key_validation_proving_key,
+        key_validation_param,
+        key_validation_public_input,
+        key_validation_secret_input,
+    );
+
+    // Generate position encryption public & secret input
+    let poseidon_encryption_public_input = PoseidonEncryptionPublicInput {
+        encrypted_data: pvde_encrypted_transaction
+            .transaction_data()
+            .encrypted_data()
+            .clone()
+            .into_inner()
+            .clone(),
+        k_hash_value: key_validation_public_input.k_hash_value.clone(),
+    };
+    let poseidon_encryption_secret_input = PoseidonEncryptionSecretInput {
+        data: to_encrypt_data.clone(),
+        k: key_validation_secret_input.k.clone(),
+    };
+    let proof_of_poseidon_encryption = prove_poseidon_encryption(
+        poseidon_encryption_zkp_param,
+        poseidon_encryption_proving_key,
+        &poseidon_encryption_public_input,
+        &poseidon_encryption_secret_input,
+    );
+
+    let public_input = PvdePublicInput::new(
+        sigma_protocol_public_input.r1.clone(),
+        sigma_protocol_public_input.r2.clone(),
+        sigma_protocol_public_input.z.clone(),
+        sigma_protocol_public_input.o.clone(),
+        key_validation_public_input.k_two.clone(),
+        key_validation_public_input.k_hash_value.clone(),
+    );
+    let time_lock_puzzle_proof = TimeLockPuzzleProof::new(proof_of_key_validation);
+    let encryption_proof = EncryptionProof::new(proof_of_poseidon_encryption);
+
+    let pvde_zkp = PvdeZkp::new(public_input, time_lock_puzzle_proof, encryption_proof);
+    pvde_encrypted_transaction.set_pvde_zkp(pvde_zkp);
+
+    Ok(pvde_encrypted_transaction)
+}
@@ -3,14 +3,14 @@ use crate::rpc::eth::prelude::*;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EthBlockNumber(Value);
 
-impl RpcParameter<AppState> for EthBlockNumber {
+impl RpcMethod for EthBlockNumber {
     type Response = Value;
 
     fn method() -> &'static str {
         "eth_blockNumber"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         super::forward(Self::method(), self, context).await
     }
 }
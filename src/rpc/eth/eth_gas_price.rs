@@ -3,14 +3,14 @@ use crate::rpc::eth::prelude::*;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EthGasPrice(Value);
 
-impl RpcParameter<AppState> for EthGasPrice {
+impl RpcMethod for EthGasPrice {
     type Response = Value;
 
     fn method() -> &'static str {
         "eth_gasPrice"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         super::forward(Self::method(), self, context).await
     }
 }
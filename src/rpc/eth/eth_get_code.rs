@@ -3,14 +3,14 @@ use crate::rpc::eth::prelude::*;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EthGetCode(Value);
 
-impl RpcParameter<AppState> for EthGetCode {
+impl RpcMethod for EthGetCode {
     type Response = Value;
 
     fn method() -> &'static str {
         "eth_getCode"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         super::forward(Self::method(), self, context).await
     }
 }
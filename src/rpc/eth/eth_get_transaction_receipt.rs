@@ -3,14 +3,14 @@ use crate::rpc::eth::prelude::*;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EthGetTransactionReceipt(Value);
 
-impl RpcParameter<AppState> for EthGetTransactionReceipt {
+impl RpcMethod for EthGetTransactionReceipt {
     type Response = Value;
 
     fn method() -> &'static str {
         "eth_getTransactionReceipt"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
         super::forward(Self::method(), self, context).await
     }
 }
@@ -0,0 +1,73 @@
+use crate::{
+    rpc::{prelude::*, SendRawTransaction},
+    validation::bundle_hash,
+};
+
+/// `eth_sendBundle([{ txs, blockNumber }])` submits signed transactions to be
+/// ordered together.
+///
+/// The sequencer decrypts every encrypted transaction into a single
+/// transaction, so bundles are refused with
+/// [`Error::EncryptedBundleUnsupported`] while encryption is enabled rather
+/// than submitted in a form the sequencer cannot decode.
+///
+/// Returns the bundle hash, the keccak256 hash of the concatenated transaction
+/// hashes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EthSendBundle(Vec<EthSendBundleParameter>);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthSendBundleParameter {
+    pub txs: Vec<String>,
+    /// Accepted for compatibility. The sequencer decides the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthSendBundleResponse {
+    pub bundle_hash: String,
+}
+
+impl RpcMethod for EthSendBundle {
+    type Response = EthSendBundleResponse;
+
+    fn method() -> &'static str {
+        "eth_sendBundle"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        if context.config().is_using_encryption() {
+            return Err(Error::EncryptedBundleUnsupported);
+        }
+
+        let parameter = self
+            .0
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyRawTransaction)?;
+        if parameter.txs.is_empty() {
+            return Err(Error::EmptyRawTransaction);
+        }
+
+        let bundle_hash = bundle_hash(&parameter.txs)?;
+
+        let raw_transaction = RawTransaction::EthBundle(EthRawBundleTransaction(
+            serde_json::to_string(&parameter.txs)?,
+        ));
+        let rollup_id = context.config().rollup_id().clone();
+
+        // The order commitment is journaled under the bundle hash and the hash
+        // of each transaction of the bundle.
+        SendRawTransaction {
+            rollup_id,
+            raw_transaction,
+        }
+        .handler(context)
+        .await?;
+
+        Ok(EthSendBundleResponse { bundle_hash })
+    }
+}
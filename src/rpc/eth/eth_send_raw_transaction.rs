@@ -1,7 +1,11 @@
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use serde_json::Value;
 
-use crate::rpc::prelude::*;
+use crate::{
+    journal::now,
+    order_commitment::{accept_order_commitment, Submission},
+    rpc::{prelude::*, send_encrypted_transaction},
+    validation::validate_raw_transaction,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EthSendRawTransaction(Vec<String>);
@@ -19,22 +23,38 @@ struct RawTransactionRequestData<'a> {
     data: &'a str,
 }
 
-impl RpcParameter<AppState> for EthSendRawTransaction {
+impl RpcMethod for EthSendRawTransaction {
     type Response = Value;
 
     fn method() -> &'static str {
         "eth_sendRawTransaction"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        let submitted_at = now();
+
         if self.0.is_empty() {
-            return Err(Error::EmptyRawTransaction.into());
+            return Err(Error::EmptyRawTransaction);
         }
 
         let raw_transaction_string = self.0.get(0).unwrap();
         let eth_raw_transaction = EthRawTransaction(raw_transaction_string.clone());
         let raw_transaction_hash = eth_raw_transaction.raw_transaction_hash();
 
+        // Wallets only speak `eth_sendRawTransaction`, so encrypt here as well.
+        if context.config().is_using_encryption() {
+            send_encrypted_transaction(
+                context.clone(),
+                context.config().rollup_id().clone(),
+                RawTransaction::Eth(eth_raw_transaction),
+            )
+            .await?;
+
+            return Ok(serde_json::to_value(raw_transaction_hash.as_string())?);
+        }
+
+        validate_raw_transaction(&context, raw_transaction_string).await?;
+
         let parameter = RawTransactionRequest {
             rollup_id: context.config().rollup_id(),
             raw_transaction: RawTransactionRequestData {
@@ -43,31 +63,27 @@ impl RpcParameter<AppState> for EthSendRawTransaction {
             },
         };
 
-        let seed: u64 = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-            .try_into()
-            .unwrap();
-
-        let _order_commitment: OrderCommitment = context
-            .rpc_client()
-            .request(
-                context
-                    .config()
-                    .sequencer_rpc_url_list()
-                    .choose(&mut StdRng::seed_from_u64(seed))
-                    .ok_or(Error::EmptySequencerRpcUrl)?,
-                "send_raw_transaction",
-                parameter,
-                Id::Null,
-            )
+        let (sequencer_rpc_url, order_commitment): (String, OrderCommitment) = context
+            .sequencer_client()
+            .request_with_rpc_url("send_raw_transaction", parameter)
             .await
-            .map_err(|e| {
-                tracing::error!("Failed to send raw transaction: {:?}", e);
-                e
+            .map_err(|error| {
+                tracing::error!("Failed to send raw transaction: {:?}", error);
+                Error::Sequencer(error)
             })?;
 
+        accept_order_commitment(
+            &context,
+            Submission {
+                raw_transaction: &RawTransaction::Eth(eth_raw_transaction),
+                rollup_id: context.config().rollup_id(),
+                encryption_key_id: None,
+                sequencer_rpc_url,
+                order_commitment,
+                submitted_at,
+            },
+        )?;
+
         Ok(serde_json::to_value(raw_transaction_hash.as_string())?)
     }
 }
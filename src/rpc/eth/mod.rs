@@ -12,7 +12,9 @@ mod eth_get_transaction_by_hash;
 mod eth_get_transaction_count;
 mod eth_get_transaction_receipt;
 mod eth_net_version;
+mod eth_send_bundle;
 mod eth_send_raw_transaction;
+mod response_cache;
 
 pub use eth_block_number::EthBlockNumber;
 pub use eth_call::EthCall;
@@ -28,34 +30,82 @@ pub use eth_get_transaction_by_hash::EthGetTransactionByHash;
 pub use eth_get_transaction_count::EthGetTransactionCount;
 pub use eth_get_transaction_receipt::EthGetTransactionReceipt;
 pub use eth_net_version::EthNetVersion;
+pub use eth_send_bundle::{EthSendBundle, EthSendBundleParameter, EthSendBundleResponse};
 pub use eth_send_raw_transaction::EthSendRawTransaction;
+pub use response_cache::{CachePolicy, ResponseCache};
 
 pub mod prelude {
-    pub use radius_sdk::json_rpc::{
-        client::{Id, RpcClient},
-        server::{RpcError, RpcParameter},
-    };
+    pub use radius_sdk::json_rpc::client::{Id, RpcClient};
     pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
-    pub use serde_json::{Error, Value};
+    pub use serde_json::Value;
 
-    pub use crate::state::AppState;
+    pub use crate::{error::Error, rpc::RpcMethod, state::AppState};
 }
 
 use prelude::*;
 
-pub async fn forward<P, R>(method: &str, parameter: P, context: AppState) -> Result<R, RpcError>
+/// Returns `true` if `method` is forwarded to the rollup as is, so that calls
+/// to it in a batch can be coalesced into a single batch to the rollup.
+pub fn is_proxied(method: &str) -> bool {
+    [
+        EthBlockNumber::method(),
+        EthCall::method(),
+        EthChainId::method(),
+        EthEstimateGas::method(),
+        EthFeeHistory::method(),
+        EthGasPrice::method(),
+        EthGetBalance::method(),
+        EthGetBlockByHash::method(),
+        EthGetBlockByNumber::method(),
+        EthGetCode::method(),
+        EthGetTransactionByHash::method(),
+        EthGetTransactionCount::method(),
+        EthGetTransactionReceipt::method(),
+        EthNetVersion::method(),
+    ]
+    .contains(&method)
+}
+
+/// Forwards a call to the rollup, serving calls with a [`CachePolicy`] from
+/// the response cache if enabled.
+pub async fn forward<P, R>(method: &str, parameter: P, context: AppState) -> Result<R, Error>
+where
+    P: Serialize,
+    R: DeserializeOwned,
+{
+    let Some(response_cache) = context.response_cache().cloned() else {
+        return request(method, parameter, context).await;
+    };
+
+    let parameter = serde_json::to_value(parameter)?;
+    if CachePolicy::of(method, &parameter).is_none() {
+        return request(method, parameter, context).await;
+    }
+
+    let response = match response_cache.get(method, &parameter) {
+        Some(response) => response,
+        None => {
+            let response: Value = request(method, &parameter, context).await?;
+            response_cache.insert(method, &parameter, &response);
+
+            response
+        }
+    };
+
+    Ok(serde_json::from_value(response)?)
+}
+
+/// Forwards a call to the rollup, bypassing the response cache.
+pub async fn request<P, R>(method: &str, parameter: P, context: AppState) -> Result<R, Error>
 where
     P: Serialize,
     R: DeserializeOwned,
 {
-    context
-        .rpc_client()
-        .request(
-            context.config().rollup_rpc_url(),
-            method,
-            parameter,
-            Id::Null,
-        )
+    let response = context
+        .rollup_client()
+        .request(method, parameter)
         .await
-        .map_err(RpcError::from)
+        .map_err(Error::Rollup)?;
+
+    Ok(response)
 }
@@ -0,0 +1,211 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use serde_json::Value;
+
+use crate::metrics::metrics;
+
+/// How long a cached response stays valid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CachePolicy {
+    /// The response never changes once available.
+    Forever,
+    /// The response changes with every block, so it is only kept for the
+    /// configured time to live.
+    ShortLived,
+}
+
+impl CachePolicy {
+    /// Returns the policy of a call, or `None` if its response is not cached.
+    pub fn of(method: &str, parameter: &Value) -> Option<Self> {
+        match method {
+            "eth_chainId"
+            | "net_version"
+            | "eth_getBlockByHash"
+            | "eth_getTransactionByHash"
+            | "eth_getTransactionReceipt" => Some(Self::Forever),
+            "eth_blockNumber" | "eth_gasPrice" => Some(Self::ShortLived),
+            // The result of a call at a fixed block never changes, unlike one
+            // at a block tag such as `latest`.
+            "eth_call" => is_fixed_block(parameter.get(1)?).then_some(Self::Forever),
+            _ => None,
+        }
+    }
+}
+
+/// Returns `true` for a block number or a block hash.
+fn is_fixed_block(block: &Value) -> bool {
+    match block {
+        Value::String(block) => block.starts_with("0x"),
+        Value::Object(block) => {
+            block.contains_key("blockHash") || block.contains_key("blockNumber")
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if the response is final and can be cached.
+///
+/// Lookups of data that is not mined yet answer `null`, and transactions
+/// still in the pool have no block hash.
+fn is_final(method: &str, response: &Value) -> bool {
+    match method {
+        "eth_getTransactionByHash" => response
+            .get("blockHash")
+            .is_some_and(|block_hash| !block_hash.is_null()),
+        _ => !response.is_null(),
+    }
+}
+
+/// Bounded LRU cache of rollup responses to immutable eth queries.
+pub struct ResponseCache {
+    inner: Arc<ResponseCacheInner>,
+}
+
+struct ResponseCacheInner {
+    rollup_id: String,
+    ttl: Duration,
+    responses: Mutex<LruCache<String, CachedResponse>>,
+}
+
+struct CachedResponse {
+    response: Value,
+    expires_at: Option<Instant>,
+}
+
+impl Clone for ResponseCache {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn new(rollup_id: String, capacity: usize, ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+
+        let inner = ResponseCacheInner {
+            rollup_id,
+            ttl,
+            responses: Mutex::new(LruCache::new(capacity)),
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Returns the cached response to a call with a cache policy.
+    pub fn get(&self, method: &str, parameter: &Value) -> Option<Value> {
+        let key = cache_key(method, parameter);
+        let mut responses = self.inner.responses.lock().unwrap();
+
+        let response = match responses.get(&key) {
+            Some(cached)
+                if cached
+                    .expires_at
+                    .map_or(true, |expires_at| expires_at > Instant::now()) =>
+            {
+                Some(cached.response.clone())
+            }
+            Some(_) => {
+                responses.pop(&key);
+                None
+            }
+            None => None,
+        };
+
+        metrics().observe_response_cache_request(&self.inner.rollup_id, method, response.is_some());
+
+        response
+    }
+
+    /// Caches the response to a call if it has a cache policy and is final.
+    pub fn insert(&self, method: &str, parameter: &Value, response: &Value) {
+        let Some(policy) = CachePolicy::of(method, parameter) else {
+            return;
+        };
+
+        if !is_final(method, response) {
+            return;
+        }
+
+        let expires_at = match policy {
+            CachePolicy::Forever => None,
+            CachePolicy::ShortLived => Some(Instant::now() + self.inner.ttl),
+        };
+
+        self.inner.responses.lock().unwrap().put(
+            cache_key(method, parameter),
+            CachedResponse {
+                response: response.clone(),
+                expires_at,
+            },
+        );
+    }
+}
+
+fn cache_key(method: &str, parameter: &Value) -> String {
+    format!("{}:{}", method, parameter)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn caches_calls_at_a_fixed_block_only() {
+        let call = json!({ "to": "0x0000000000000000000000000000000000000001" });
+
+        for block in [json!("latest"), json!("pending"), json!("safe")] {
+            assert_eq!(CachePolicy::of("eth_call", &json!([call, block])), None);
+        }
+        assert_eq!(CachePolicy::of("eth_call", &json!([call])), None);
+
+        for block in [
+            json!("0x10"),
+            json!({ "blockNumber": "0x10" }),
+            json!({ "blockHash": "0x01" }),
+        ] {
+            assert_eq!(
+                CachePolicy::of("eth_call", &json!([call, block])),
+                Some(CachePolicy::Forever)
+            );
+        }
+    }
+
+    #[test]
+    fn caches_final_responses_only() {
+        assert!(!is_final("eth_getTransactionReceipt", &Value::Null));
+        assert!(!is_final(
+            "eth_getTransactionByHash",
+            &json!({ "hash": "0x01", "blockHash": null })
+        ));
+        assert!(is_final(
+            "eth_getTransactionByHash",
+            &json!({ "hash": "0x01", "blockHash": "0x02" })
+        ));
+
+        let response_cache = ResponseCache::new("0".into(), 16, Duration::from_secs(60));
+        let parameter = json!(["0x01"]);
+
+        response_cache.insert("eth_getTransactionReceipt", &parameter, &Value::Null);
+        assert!(response_cache
+            .get("eth_getTransactionReceipt", &parameter)
+            .is_none());
+
+        let receipt = json!({ "transactionHash": "0x01", "blockHash": "0x02" });
+        response_cache.insert("eth_getTransactionReceipt", &parameter, &receipt);
+        assert_eq!(
+            response_cache.get("eth_getTransactionReceipt", &parameter),
+            Some(receipt)
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+use radius_sdk::json_rpc::server::{RpcError, RpcParameter, RpcServer, RpcServerError};
+
+use crate::{
+    metrics::metrics,
+    rpc::{prelude::*, MethodRegistry, RpcMethod},
+};
+
+/// Registers an [`RpcMethod`] on the internal RPC server, recording request
+/// count and latency metrics around it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Instrumented<T>(pub T);
+
+impl<T> RpcParameter<AppState> for Instrumented<T>
+where
+    T: RpcMethod,
+{
+    type Response = T::Response;
+
+    fn method() -> &'static str {
+        T::method()
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup_id = context.config().rollup_id().clone();
+        let started_at = Instant::now();
+
+        let response = self.0.handler(context).await;
+        metrics().observe_rpc_request(
+            &rollup_id,
+            T::method(),
+            response.is_ok(),
+            started_at.elapsed(),
+        );
+
+        response.map_err(RpcError::from)
+    }
+}
+
+impl MethodRegistry for RpcServer<AppState> {
+    type Error = RpcServerError;
+
+    fn register_method<T: RpcMethod>(self) -> Result<Self, Self::Error> {
+        self.register_rpc_method::<Instrumented<T>>()
+    }
+}
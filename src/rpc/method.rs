@@ -0,0 +1,97 @@
+use std::{collections::HashMap, convert::Infallible, fmt::Debug, future::Future, time::Instant};
+
+use futures_util::future::BoxFuture;
+use serde_json::Value;
+
+use crate::{metrics::metrics, rpc::prelude::*};
+
+/// A method of the RPC server.
+///
+/// Handlers fail with [`Error`] rather than an opaque RPC error, so that the
+/// gateway, which calls them in-process through [`MethodRouter`], answers with
+/// the code and data of the error. [`Instrumented`](super::Instrumented)
+/// registers them on the internal RPC server.
+pub trait RpcMethod: Clone + Debug + DeserializeOwned + Serialize + Send + 'static {
+    type Response: Clone + Debug + DeserializeOwned + Serialize + Send + 'static;
+
+    fn method() -> &'static str;
+
+    fn handler(
+        self,
+        context: AppState,
+    ) -> impl Future<Output = Result<Self::Response, Error>> + Send;
+}
+
+/// A router or server that [`RpcMethod`]s are registered on, so that the
+/// gateway and the internal RPC server are built from the same method list.
+pub trait MethodRegistry: Sized {
+    type Error;
+
+    fn register_method<T: RpcMethod>(self) -> Result<Self, Self::Error>;
+}
+
+type Handler = fn(AppState, Value) -> BoxFuture<'static, Result<Value, Error>>;
+
+/// Calls the handlers of [`RpcMethod`]s by method name.
+#[derive(Default)]
+pub struct MethodRouter {
+    handler_list: HashMap<&'static str, Handler>,
+}
+
+impl MethodRouter {
+    pub fn register<T: RpcMethod>(mut self) -> Self {
+        self.handler_list.insert(T::method(), call::<T>);
+        self
+    }
+
+    pub fn contains(&self, method: &str) -> bool {
+        self.handler_list.contains_key(method)
+    }
+
+    /// Calls the handler of `method` with the `params` of a JSON-RPC request,
+    /// `null` if the request has none.
+    pub async fn call(
+        &self,
+        context: AppState,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, Error> {
+        let handler = self
+            .handler_list
+            .get(method)
+            .ok_or_else(|| Error::MethodNotFound(method.to_owned()))?;
+
+        handler(context, params).await
+    }
+}
+
+impl MethodRegistry for MethodRouter {
+    type Error = Infallible;
+
+    fn register_method<T: RpcMethod>(self) -> Result<Self, Self::Error> {
+        Ok(self.register::<T>())
+    }
+}
+
+fn call<T: RpcMethod>(
+    context: AppState,
+    params: Value,
+) -> BoxFuture<'static, Result<Value, Error>> {
+    Box::pin(async move {
+        let parameter: T = serde_json::from_value(params)
+            .map_err(|error| Error::InvalidParams(error.to_string()))?;
+
+        let rollup_id = context.config().rollup_id().clone();
+        let started_at = Instant::now();
+
+        let response = parameter.handler(context).await;
+        metrics().observe_rpc_request(
+            &rollup_id,
+            T::method(),
+            response.is_ok(),
+            started_at.elapsed(),
+        );
+
+        Ok(serde_json::to_value(response?)?)
+    })
+}
@@ -1,21 +1,97 @@
+use std::sync::LazyLock;
+
 pub mod prelude {
-    pub use radius_sdk::json_rpc::{
-        client::{Id, RpcClient},
-        server::{RpcError, RpcParameter},
-    };
+    pub use radius_sdk::json_rpc::client::{Id, RpcClient};
     pub use sequencer::types::*;
     pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+    pub use super::RpcMethod;
     pub use crate::{error::Error, state::AppState};
 }
 
+pub mod admin;
 mod decrypt_transaction;
 mod encrypt_transaction;
 pub mod eth;
+mod instrumented;
+mod method;
+pub mod secure;
 mod send_encrypted_transaction;
 mod send_raw_transaction;
 
 pub use decrypt_transaction::DecryptTransaction;
 pub use encrypt_transaction::EncryptTransaction;
-pub use send_encrypted_transaction::SendEncryptedTransaction;
+pub use instrumented::Instrumented;
+pub use method::{MethodRegistry, MethodRouter, RpcMethod};
+pub use send_encrypted_transaction::{send_encrypted_transaction, SendEncryptedTransaction};
 pub use send_raw_transaction::SendRawTransaction;
+
+static METHOD_ROUTER: LazyLock<MethodRouter> = LazyLock::new(|| {
+    register_methods(MethodRouter::default()).unwrap_or_else(|error| match error {})
+});
+
+/// Registers the methods served to clients, through the gateway and on the
+/// internal RPC server alike.
+pub fn register_methods<R: MethodRegistry>(registry: R) -> Result<R, R::Error> {
+    registry
+        // eth
+        .register_method::<eth::EthBlockNumber>()?
+        .register_method::<eth::EthCall>()?
+        .register_method::<eth::EthChainId>()?
+        .register_method::<eth::EthEstimateGas>()?
+        .register_method::<eth::EthFeeHistory>()?
+        .register_method::<eth::EthGasPrice>()?
+        .register_method::<eth::EthGetBalance>()?
+        .register_method::<eth::EthGetBlockByHash>()?
+        .register_method::<eth::EthGetBlockByNumber>()?
+        .register_method::<eth::EthGetCode>()?
+        .register_method::<eth::EthGetTransactionByHash>()?
+        .register_method::<eth::EthGetTransactionCount>()?
+        .register_method::<eth::EthGetTransactionReceipt>()?
+        .register_method::<eth::EthNetVersion>()?
+        .register_method::<eth::EthSendRawTransaction>()?
+        .register_method::<eth::EthSendBundle>()?
+        // cryptography
+        .register_method::<DecryptTransaction>()?
+        .register_method::<EncryptTransaction>()?
+        // sequencer
+        .register_method::<SendEncryptedTransaction>()?
+        .register_method::<SendRawTransaction>()?
+        // secure
+        .register_method::<secure::SecureGetSubmission>()?
+        .register_method::<secure::SecureGetOrderCommitment>()
+}
+
+/// Registers the methods of the internal RPC server, the client methods and
+/// the `admin_*` methods, which the gateway never serves.
+pub fn register_internal_methods<R: MethodRegistry>(registry: R) -> Result<R, R::Error> {
+    register_methods(registry)?
+        // admin
+        .register_method::<admin::AdminGetSequencerPool>()
+}
+
+/// Returns the methods the gateway serves in-process.
+pub fn method_router() -> &'static MethodRouter {
+    &METHOD_ROUTER
+}
+
+/// Returns `true` if `method` is served by the RPC server.
+pub fn is_native(method: &str) -> bool {
+    method_router().contains(method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_admin_methods_on_the_internal_rpc_server_only() {
+        let internal_method_router = register_internal_methods(MethodRouter::default())
+            .unwrap_or_else(|error| match error {});
+
+        assert!(internal_method_router.contains("admin_get_sequencer_pool"));
+        assert!(internal_method_router.contains("send_encrypted_transaction"));
+        assert!(!is_native("admin_get_sequencer_pool"));
+        assert!(is_native("send_encrypted_transaction"));
+    }
+}
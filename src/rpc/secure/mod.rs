@@ -0,0 +1,5 @@
+mod secure_get_order_commitment;
+mod secure_get_submission;
+
+pub use secure_get_order_commitment::{SecureGetOrderCommitment, SecureGetOrderCommitmentResponse};
+pub use secure_get_submission::{SecureGetSubmission, SecureGetSubmissionResponse};
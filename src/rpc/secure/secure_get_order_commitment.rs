@@ -0,0 +1,42 @@
+use crate::rpc::prelude::*;
+
+/// `secure_getOrderCommitment(transactionHash)` returns the order commitment
+/// the sequencer issued for a transaction submitted through this instance,
+/// including `eth_sendRawTransaction`, or `null` if there is none. Bundles are
+/// found by their bundle hash and by the hash of each of their transactions.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecureGetOrderCommitment(Vec<String>);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecureGetOrderCommitmentResponse {
+    pub transaction_hash: String,
+    pub rollup_id: String,
+    /// The bundle hash, if the transaction was submitted in a bundle.
+    pub bundle_hash: Option<String>,
+    /// The signed order commitment as issued by the sequencer.
+    pub order_commitment: OrderCommitment,
+    pub committed_at: u64,
+}
+
+impl RpcMethod for SecureGetOrderCommitment {
+    type Response = Option<SecureGetOrderCommitmentResponse>;
+
+    fn method() -> &'static str {
+        "secure_getOrderCommitment"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        let transaction_hash = self.0.first().ok_or(Error::EmptyTransactionHash)?;
+
+        Ok(context
+            .submission_journal()
+            .get(context.config().rollup_id(), transaction_hash)
+            .map(|record| SecureGetOrderCommitmentResponse {
+                transaction_hash: record.transaction_hash,
+                rollup_id: record.rollup_id,
+                bundle_hash: record.bundle_hash,
+                order_commitment: record.order_commitment,
+                committed_at: record.committed_at,
+            }))
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{journal::SubmissionRecord, rpc::prelude::*};
+
+/// `secure_getSubmission(transactionHash)` returns the journaled submission of
+/// the transaction or bundle to this rollup, or `null` if this instance never
+/// submitted it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecureGetSubmission(Vec<String>);
+
+/// A journaled submission without the sequencer that ordered it, which is
+/// internal topology and only kept in the journal.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecureGetSubmissionResponse {
+    pub transaction_hash: String,
+    pub rollup_id: String,
+    pub sender: Option<String>,
+    pub bundle_hash: Option<String>,
+    pub encryption_key_id: Option<u64>,
+    pub order_commitment: OrderCommitment,
+    pub is_order_commitment_valid: Option<bool>,
+    pub submitted_at: u64,
+    pub committed_at: u64,
+}
+
+impl From<SubmissionRecord> for SecureGetSubmissionResponse {
+    fn from(record: SubmissionRecord) -> Self {
+        Self {
+            transaction_hash: record.transaction_hash,
+            rollup_id: record.rollup_id,
+            sender: record.sender,
+            bundle_hash: record.bundle_hash,
+            encryption_key_id: record.encryption_key_id,
+            order_commitment: record.order_commitment,
+            is_order_commitment_valid: record.is_order_commitment_valid,
+            submitted_at: record.submitted_at,
+            committed_at: record.committed_at,
+        }
+    }
+}
+
+impl RpcMethod for SecureGetSubmission {
+    type Response = Option<SecureGetSubmissionResponse>;
+
+    fn method() -> &'static str {
+        "secure_getSubmission"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        let transaction_hash = self.0.first().ok_or(Error::EmptyTransactionHash)?;
+
+        Ok(context
+            .submission_journal()
+            .get(context.config().rollup_id(), transaction_hash)
+            .map(SecureGetSubmissionResponse::from))
+    }
+}
@@ -1,6 +1,9 @@
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
-
-use crate::rpc::{prelude::*, EncryptTransaction};
+use crate::{
+    journal::now,
+    order_commitment::{accept_order_commitment, Submission},
+    rpc::{prelude::*, EncryptTransaction},
+    validation::validate_rollup_id,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SendEncryptedTransaction {
@@ -14,59 +17,75 @@ struct SendEncryptedTransactionRequest {
     encrypted_transaction: EncryptedTransaction,
 }
 
-impl RpcParameter<AppState> for SendEncryptedTransaction {
+impl RpcMethod for SendEncryptedTransaction {
     type Response = OrderCommitment;
 
     fn method() -> &'static str {
         "send_encrypted_transaction"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
-        if !context.config().is_using_encryption() {
-            return Err(Error::EncryptionNotEnabled.into());
-        }
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        send_encrypted_transaction(context, self.rollup_id, self.raw_transaction).await
+    }
+}
+
+/// Encrypts `raw_transaction` with the configured encrypted transaction type
+/// and submits it to the sequencer. Shared by `send_encrypted_transaction` and
+/// the encrypted mode of `eth_sendRawTransaction`.
+pub async fn send_encrypted_transaction(
+    context: AppState,
+    rollup_id: String,
+    raw_transaction: RawTransaction,
+) -> Result<OrderCommitment, Error> {
+    if !context.config().is_using_encryption() {
+        return Err(Error::EncryptionNotEnabled);
+    }
+
+    validate_rollup_id(&context, &rollup_id)?;
+
+    let submitted_at = now();
 
-        tracing::info!("encrypt_transaction_params: {:?}", self.raw_transaction);
-        let encrypt_transaction_request = EncryptTransaction {
-            raw_transaction: self.raw_transaction,
-        };
-        let encrypt_transaction_response =
-            encrypt_transaction_request.handler(context.clone()).await?;
+    tracing::info!("encrypt_transaction_params: {:?}", raw_transaction);
+    let encrypt_transaction_request = EncryptTransaction {
+        raw_transaction: raw_transaction.clone(),
+    };
+    let encrypt_transaction_response = encrypt_transaction_request.handler(context.clone()).await?;
 
-        let parameter = SendEncryptedTransactionRequest {
-            rollup_id: self.rollup_id,
-            encrypted_transaction: encrypt_transaction_response.encrypted_transaction,
-        };
+    let encryption_key_id = match &encrypt_transaction_response.encrypted_transaction {
+        EncryptedTransaction::Skde(encrypted_transaction) => Some(encrypted_transaction.key_id),
+        _ => None,
+    };
 
-        let seed: u64 = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-            .try_into()
-            .unwrap();
+    let parameter = SendEncryptedTransactionRequest {
+        rollup_id: rollup_id.clone(),
+        encrypted_transaction: encrypt_transaction_response.encrypted_transaction,
+    };
 
-        match context
-            .rpc_client()
-            .request(
-                context
-                    .config()
-                    .sequencer_rpc_url_list()
-                    .choose(&mut StdRng::seed_from_u64(seed))
-                    .ok_or(Error::EmptySequencerRpcUrl)?,
-                Self::method(),
-                parameter,
-                Id::Null,
-            )
-            .await
-        {
-            Ok(order_commitment) => {
-                tracing::info!("Order commitment: {:?}", order_commitment);
-                Ok(order_commitment)
-            }
-            Err(error) => {
-                tracing::error!("Failed to send encrypted transaction: {:?}", error);
-                Err(error.into())
-            }
+    match context
+        .sequencer_client()
+        .request_with_rpc_url(SendEncryptedTransaction::method(), parameter)
+        .await
+    {
+        Ok((sequencer_rpc_url, order_commitment)) => {
+            tracing::info!("Order commitment: {:?}", order_commitment);
+
+            let order_commitment = accept_order_commitment(
+                &context,
+                Submission {
+                    raw_transaction: &raw_transaction,
+                    rollup_id: &rollup_id,
+                    encryption_key_id,
+                    sequencer_rpc_url,
+                    order_commitment,
+                    submitted_at,
+                },
+            )?;
+
+            Ok(order_commitment)
+        }
+        Err(error) => {
+            tracing::error!("Failed to send encrypted transaction: {:?}", error);
+            Err(Error::Sequencer(error))
         }
     }
 }
@@ -1,6 +1,9 @@
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
-
-use crate::rpc::prelude::*;
+use crate::{
+    journal::now,
+    order_commitment::{accept_order_commitment, Submission},
+    rpc::prelude::*,
+    validation::{validate_bundle, validate_raw_transaction, validate_rollup_id},
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SendRawTransaction {
@@ -8,42 +11,52 @@ pub struct SendRawTransaction {
     pub raw_transaction: RawTransaction,
 }
 
-impl RpcParameter<AppState> for SendRawTransaction {
+impl RpcMethod for SendRawTransaction {
     type Response = OrderCommitment;
 
     fn method() -> &'static str {
         "send_raw_transaction"
     }
 
-    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
-        let seed: u64 = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-            .try_into()
-            .unwrap();
+    async fn handler(self, context: AppState) -> Result<Self::Response, Error> {
+        let submitted_at = now();
+
+        validate_rollup_id(&context, &self.rollup_id)?;
+
+        match &self.raw_transaction {
+            RawTransaction::Eth(raw_transaction) => {
+                validate_raw_transaction(&context, &raw_transaction.0).await?;
+            }
+            RawTransaction::EthBundle(raw_bundle) => {
+                validate_bundle(&context, &raw_bundle.0).await?;
+            }
+        }
 
         match context
-            .rpc_client()
-            .request(
-                context
-                    .config()
-                    .sequencer_rpc_url_list()
-                    .choose(&mut StdRng::seed_from_u64(seed))
-                    .ok_or(Error::EmptySequencerRpcUrl)?,
-                Self::method(),
-                self,
-                Id::Null,
-            )
+            .sequencer_client()
+            .request_with_rpc_url(Self::method(), &self)
             .await
         {
-            Ok(order_commitment) => {
+            Ok((sequencer_rpc_url, order_commitment)) => {
                 tracing::info!("Order commitment: {:?}", order_commitment);
+
+                let order_commitment = accept_order_commitment(
+                    &context,
+                    Submission {
+                        raw_transaction: &self.raw_transaction,
+                        rollup_id: &self.rollup_id,
+                        encryption_key_id: None,
+                        sequencer_rpc_url,
+                        order_commitment,
+                        submitted_at,
+                    },
+                )?;
+
                 Ok(order_commitment)
             }
             Err(error) => {
                 tracing::error!("Failed to send raw transaction: {:?}", error);
-                Err(error.into())
+                Err(Error::Sequencer(error))
             }
         }
     }
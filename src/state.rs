@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use pvde::{
     halo2_proofs::{
@@ -11,7 +11,14 @@ use pvde::{
 use radius_sdk::{context::SharedContext, json_rpc::client::RpcClient};
 
 use crate::{
-    client::distributed_key_generation::DistributedKeyGenerationClient, types::config::Config,
+    client::{
+        distributed_key_generation::DistributedKeyGenerationClient, rollup::RollupClient,
+        sequencer::SequencerClient,
+    },
+    error::Error,
+    journal::SubmissionJournal,
+    rpc::eth::ResponseCache,
+    types::{config::Config, CachedEncryptionKey, EncryptionKeyCache},
 };
 
 pub struct AppState {
@@ -22,8 +29,14 @@ struct AppStateInner {
     config: Config,
     rpc_client: RpcClient,
     pvde_params: SharedContext<Option<PvdeParams>>,
-    skde_params: skde::delay_encryption::SkdeParams,
+    skde_params: Option<skde::delay_encryption::SkdeParams>,
     distributed_key_generation_client: Option<DistributedKeyGenerationClient>,
+    encryption_key_cache: EncryptionKeyCache,
+    sequencer_client: SequencerClient,
+    rollup_client: RollupClient,
+    submission_journal: SubmissionJournal,
+    rollup_chain_id: OnceLock<u64>,
+    response_cache: Option<ResponseCache>,
 }
 
 impl Clone for AppState {
@@ -37,15 +50,32 @@ impl Clone for AppState {
 impl AppState {
     pub fn new(
         config: Config,
-        skde_params: skde::delay_encryption::SkdeParams,
+        skde_params: Option<skde::delay_encryption::SkdeParams>,
         distributed_key_generation_client: Option<DistributedKeyGenerationClient>,
+        sequencer_client: SequencerClient,
+        rollup_client: RollupClient,
+        submission_journal: SubmissionJournal,
     ) -> Self {
+        let response_cache = config.is_using_response_cache().then(|| {
+            ResponseCache::new(
+                config.rollup_id().clone(),
+                config.response_cache_capacity(),
+                config.response_cache_ttl(),
+            )
+        });
+
         let inner = AppStateInner {
             config,
             rpc_client: RpcClient::new().unwrap(),
             pvde_params: SharedContext::from(None),
             skde_params,
             distributed_key_generation_client,
+            encryption_key_cache: EncryptionKeyCache::default(),
+            sequencer_client,
+            rollup_client,
+            submission_journal,
+            rollup_chain_id: OnceLock::new(),
+            response_cache,
         };
 
         Self {
@@ -65,13 +95,48 @@ impl AppState {
         self.inner.pvde_params.clone()
     }
 
-    pub fn skde_params(&self) -> &skde::delay_encryption::SkdeParams {
+    pub fn skde_params(&self) -> &Option<skde::delay_encryption::SkdeParams> {
         &self.inner.skde_params
     }
 
     pub fn distributed_key_generation_client(&self) -> &Option<DistributedKeyGenerationClient> {
         &self.inner.distributed_key_generation_client
     }
+
+    pub fn sequencer_client(&self) -> &SequencerClient {
+        &self.inner.sequencer_client
+    }
+
+    pub fn rollup_client(&self) -> &RollupClient {
+        &self.inner.rollup_client
+    }
+
+    pub fn submission_journal(&self) -> &SubmissionJournal {
+        &self.inner.submission_journal
+    }
+
+    pub fn encryption_key_cache(&self) -> &EncryptionKeyCache {
+        &self.inner.encryption_key_cache
+    }
+
+    /// Returns the cached encryption key if it is fresh enough to encrypt to.
+    pub fn latest_encryption_key(&self) -> Result<CachedEncryptionKey, Error> {
+        self.inner
+            .encryption_key_cache
+            .get(self.config().encryption_key_max_age())
+    }
+
+    pub fn rollup_chain_id(&self) -> Option<u64> {
+        self.inner.rollup_chain_id.get().copied()
+    }
+
+    pub fn set_rollup_chain_id(&self, chain_id: u64) {
+        let _ = self.inner.rollup_chain_id.set(chain_id);
+    }
+
+    pub fn response_cache(&self) -> Option<&ResponseCache> {
+        self.inner.response_cache.as_ref()
+    }
 }
 
 // TODO: Import from sequencer
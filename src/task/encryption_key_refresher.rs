@@ -0,0 +1,45 @@
+use crate::{error::Error, state::AppState};
+
+/// Keeps the SKDE encryption key cache fresh by polling the distributed key
+/// generation node on the configured interval.
+pub async fn run(context: AppState) {
+    let refresh_interval = context.config().encryption_key_refresh_interval();
+
+    loop {
+        if let Err(error) = refresh(&context).await {
+            tracing::warn!("Failed to refresh the encryption key: {:?}", error);
+            context
+                .encryption_key_cache()
+                .set_last_error(format!("{:?}", error));
+        }
+
+        tokio::time::sleep(refresh_interval).await;
+    }
+}
+
+/// Fetches the latest encryption key once and stores it in the cache.
+pub async fn refresh(context: &AppState) -> Result<(), Error> {
+    let distributed_key_generation_client = context
+        .distributed_key_generation_client()
+        .as_ref()
+        .ok_or(Error::Uninitialized)?;
+
+    let get_latest_encryption_key_response = distributed_key_generation_client
+        .get_latest_encryption_key()
+        .await
+        .map_err(Error::DistributedKeyGenerationClient)?;
+
+    let is_changed = context.encryption_key_cache().update(
+        get_latest_encryption_key_response.key_id,
+        get_latest_encryption_key_response.encryption_key,
+    );
+
+    if is_changed {
+        tracing::info!(
+            "Updated the cached encryption key (key_id: {})",
+            get_latest_encryption_key_response.key_id
+        );
+    }
+
+    Ok(())
+}
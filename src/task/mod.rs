@@ -0,0 +1,3 @@
+pub mod encryption_key_refresher;
+pub mod rollup_health_checker;
+pub mod sequencer_health_checker;
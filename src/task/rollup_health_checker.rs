@@ -0,0 +1,14 @@
+use crate::state::AppState;
+
+/// Periodically probes every rollup rpc url so that unhealthy ones are ejected
+/// from the selection pool and re-admitted once they recover, and lagging ones
+/// are skipped.
+pub async fn run(context: AppState) {
+    let health_check_interval = context.config().rollup_health_check_interval();
+
+    loop {
+        context.rollup_client().check_health().await;
+
+        tokio::time::sleep(health_check_interval).await;
+    }
+}
@@ -0,0 +1,13 @@
+use crate::state::AppState;
+
+/// Periodically probes every sequencer so that unhealthy ones are ejected from
+/// the selection pool and re-admitted once they recover.
+pub async fn run(context: AppState) {
+    let health_check_interval = context.config().sequencer_health_check_interval();
+
+    loop {
+        context.sequencer_client().check_health().await;
+
+        tokio::time::sleep(health_check_interval).await;
+    }
+}
@@ -11,8 +11,58 @@ const DEFAULT_SEQUENCER_RPC_URL_LIST: &str = "http://127.0.0.1:3000";
 const DEFAULT_ROLLUP_RPC_URL: &str = "http://127.0.0.1:8123";
 const DEFAULT_ENCRYPTED_TRANSACTION_TYPE: &str = "skde";
 const DEFAULT_DISTRIBUTED_KEY_GENERATION_RPC_URL: &str = "http://127.0.0.1:7100";
+pub const DEFAULT_ENCRYPTION_KEY_REFRESH_INTERVAL_MS: u64 = 1000;
+pub const DEFAULT_ENCRYPTION_KEY_MAX_AGE_MS: u64 = 10000;
+pub const DEFAULT_SEQUENCER_MAX_ATTEMPTS: usize = 3;
+pub const DEFAULT_SEQUENCER_ATTEMPT_TIMEOUT_MS: u64 = 5000;
+pub const DEFAULT_SEQUENCER_RETRY_BACKOFF_MS: u64 = 100;
+pub const DEFAULT_SEQUENCER_HEALTH_CHECK_INTERVAL_MS: u64 = 5000;
+pub const DEFAULT_SEQUENCER_UNHEALTHY_THRESHOLD: u32 = 3;
+pub const DEFAULT_SEQUENCER_HEALTHY_THRESHOLD: u32 = 2;
+pub const DEFAULT_INTERNAL_RPC_URL: &str = "http://127.0.0.1:9001";
+pub const DEFAULT_METRICS_URL: &str = "http://127.0.0.1:9100";
+pub const DEFAULT_IS_USING_RATE_LIMIT: bool = false;
+pub const DEFAULT_RATE_LIMIT_READ_REQUESTS_PER_SECOND: u32 = 100;
+pub const DEFAULT_RATE_LIMIT_READ_BURST: u32 = 200;
+pub const DEFAULT_RATE_LIMIT_EXPENSIVE_REQUESTS_PER_SECOND: u32 = 5;
+pub const DEFAULT_RATE_LIMIT_EXPENSIVE_BURST: u32 = 10;
+pub const DEFAULT_IS_RATE_LIMITING_BY_API_KEY: bool = false;
+pub const DEFAULT_IS_USING_API_KEY_AUTH: bool = false;
+pub const DEFAULT_IS_USING_TRANSACTION_VALIDATION: bool = true;
+pub const DEFAULT_MAX_RAW_TRANSACTION_SIZE: usize = 131072;
+pub const DEFAULT_MAX_TRANSACTION_GAS_LIMIT: u64 = 30000000;
+pub const DEFAULT_IS_USING_ACCOUNT_PRECHECK: bool = false;
+pub const DEFAULT_SEQUENCER_ADDRESS_LIST: &str = "";
+pub const DEFAULT_ORDER_COMMITMENT_VERIFICATION: &str = "disabled";
+pub const DEFAULT_IS_USING_WEBSOCKET: bool = false;
+pub const DEFAULT_ROLLUP_WEBSOCKET_URL: &str = "ws://127.0.0.1:8546";
+pub const DEFAULT_WEBSOCKET_MAX_SUBSCRIPTIONS: usize = 16;
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+pub const DEFAULT_IS_USING_PASSTHROUGH: bool = false;
+pub const DEFAULT_PASSTHROUGH_ALLOW_LIST: &str = "eth_*,net_*,web3_*,debug_traceTransaction";
+// Submissions other than the encrypted and sequenced ones served by the RPC
+// server must never reach the rollup in plaintext.
+pub const DEFAULT_PASSTHROUGH_DENY_LIST: &str =
+    "eth_send*,eth_sign*,eth_accounts,admin_*,personal_*,miner_*";
+pub const DEFAULT_IS_USING_RESPONSE_CACHE: bool = true;
+pub const DEFAULT_RESPONSE_CACHE_CAPACITY: usize = 4096;
+pub const DEFAULT_RESPONSE_CACHE_TTL_MS: u64 = 1000;
+pub const DEFAULT_ROLLUP_RPC_URL_LIST: &str = "";
+pub const DEFAULT_ROLLUP_RPC_WEIGHT_LIST: &str = "";
+pub const DEFAULT_ROLLUP_SELECTION: &str = "weighted_round_robin";
+pub const DEFAULT_ROLLUP_MAX_BLOCK_LAG: u64 = 5;
+pub const DEFAULT_ROLLUP_HEALTH_CHECK_INTERVAL_MS: u64 = 1000;
+pub const DEFAULT_ROLLUP_UNHEALTHY_THRESHOLD: u32 = 3;
+pub const DEFAULT_ROLLUP_HEALTHY_THRESHOLD: u32 = 2;
+pub const DEFAULT_IS_USING_ROLLUP_PROFILES: bool = false;
+pub const DEFAULT_ROLLUP_ATTEMPT_TIMEOUT_MS: u64 = 5000;
+pub const DEFAULT_KEY_CACHE_CAPACITY: usize = 4096;
+pub const DEFAULT_UNAVAILABLE_KEY_TTL_MS: u64 = 500;
+// 30 days
+pub const DEFAULT_SUBMISSION_JOURNAL_RETENTION_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+pub const DEFAULT_SUBMISSION_JOURNAL_CAPACITY: usize = 100_000;
 
-#[derive(Debug, Deserialize, Parser, Serialize)]
+#[derive(Clone, Debug, Deserialize, Parser, Serialize)]
 pub struct ConfigOption {
     #[doc = "Set the configuration file path to load from"]
     #[clap(long = "path")]
@@ -49,6 +99,190 @@ pub struct ConfigOption {
     #[doc = "Set the distributed key generation rpc url"]
     #[clap(long = "distributed-key-generation-rpc-url")]
     pub distributed_key_generation_rpc_url: Option<String>,
+
+    #[doc = "Set the interval (ms) to refresh the cached encryption key"]
+    #[clap(long = "encryption-key-refresh-interval-ms")]
+    pub encryption_key_refresh_interval_ms: Option<u64>,
+
+    #[doc = "Set the maximum age (ms) of the cached encryption key"]
+    #[clap(long = "encryption-key-max-age-ms")]
+    pub encryption_key_max_age_ms: Option<u64>,
+
+    #[doc = "Set the maximum number of attempts to send a request to the sequencers"]
+    #[clap(long = "sequencer-max-attempts")]
+    pub sequencer_max_attempts: Option<usize>,
+
+    #[doc = "Set the timeout (ms) of a single request to a sequencer"]
+    #[clap(long = "sequencer-attempt-timeout-ms")]
+    pub sequencer_attempt_timeout_ms: Option<u64>,
+
+    #[doc = "Set the backoff (ms) before retrying a request on another sequencer"]
+    #[clap(long = "sequencer-retry-backoff-ms")]
+    pub sequencer_retry_backoff_ms: Option<u64>,
+
+    #[doc = "Set the interval (ms) to check the health of the sequencers"]
+    #[clap(long = "sequencer-health-check-interval-ms")]
+    pub sequencer_health_check_interval_ms: Option<u64>,
+
+    #[doc = "Set the number of consecutive failures to eject a sequencer"]
+    #[clap(long = "sequencer-unhealthy-threshold")]
+    pub sequencer_unhealthy_threshold: Option<u32>,
+
+    #[doc = "Set the number of consecutive successes to re-admit a sequencer"]
+    #[clap(long = "sequencer-healthy-threshold")]
+    pub sequencer_healthy_threshold: Option<u32>,
+
+    #[doc = "Set the internal rpc url served behind the gateway"]
+    #[clap(long = "internal-rpc-url")]
+    pub internal_rpc_url: Option<String>,
+
+    #[doc = "Set the metrics url"]
+    #[clap(long = "metrics-url")]
+    pub metrics_url: Option<String>,
+
+    #[doc = "Set using rate limiting on the external rpc url"]
+    #[clap(long = "is-using-rate-limit")]
+    pub is_using_rate_limit: Option<bool>,
+
+    #[doc = "Set the read requests per second allowed per client"]
+    #[clap(long = "rate-limit-read-requests-per-second")]
+    pub rate_limit_read_requests_per_second: Option<u32>,
+
+    #[doc = "Set the read request burst allowed per client"]
+    #[clap(long = "rate-limit-read-burst")]
+    pub rate_limit_read_burst: Option<u32>,
+
+    #[doc = "Set the encryption and submission requests per second allowed per client"]
+    #[clap(long = "rate-limit-expensive-requests-per-second")]
+    pub rate_limit_expensive_requests_per_second: Option<u32>,
+
+    #[doc = "Set the encryption and submission request burst allowed per client"]
+    #[clap(long = "rate-limit-expensive-burst")]
+    pub rate_limit_expensive_burst: Option<u32>,
+
+    #[doc = "Set rate limiting clients by the bearer api key instead of the ip address"]
+    #[clap(long = "is-rate-limiting-by-api-key")]
+    pub is_rate_limiting_by_api_key: Option<bool>,
+
+    #[doc = "Set using api key authentication with the api keys file in the config path"]
+    #[clap(long = "is-using-api-key-auth")]
+    pub is_using_api_key_auth: Option<bool>,
+
+    #[doc = "Set validating raw transactions before encryption and submission"]
+    #[clap(long = "is-using-transaction-validation")]
+    pub is_using_transaction_validation: Option<bool>,
+
+    #[doc = "Set the maximum size of a raw transaction in bytes"]
+    #[clap(long = "max-raw-transaction-size")]
+    pub max_raw_transaction_size: Option<usize>,
+
+    #[doc = "Set the maximum gas limit of a transaction"]
+    #[clap(long = "max-transaction-gas-limit")]
+    pub max_transaction_gas_limit: Option<u64>,
+
+    #[doc = "Set checking the sender nonce and balance against the rollup before sequencing"]
+    #[clap(long = "is-using-account-precheck")]
+    pub is_using_account_precheck: Option<bool>,
+
+    #[doc = "Set the cluster sequencer addresses allowed to sign order commitments"]
+    #[clap(long = "sequencer-address-list")]
+    pub sequencer_address_list: Option<String>,
+
+    #[doc = "Set the order commitment verification (disabled, flag or reject)"]
+    #[clap(long = "order-commitment-verification")]
+    pub order_commitment_verification: Option<String>,
+
+    #[doc = "Set serving websocket connections on the external rpc url"]
+    #[clap(long = "is-using-websocket")]
+    pub is_using_websocket: Option<bool>,
+
+    #[doc = "Set the rollup websocket url subscriptions are proxied to"]
+    #[clap(long = "rollup-websocket-url")]
+    pub rollup_websocket_url: Option<String>,
+
+    #[doc = "Set the maximum number of subscriptions per websocket connection"]
+    #[clap(long = "websocket-max-subscriptions")]
+    pub websocket_max_subscriptions: Option<usize>,
+
+    #[doc = "Set the maximum number of calls in a batch request"]
+    #[clap(long = "max-batch-size")]
+    pub max_batch_size: Option<usize>,
+
+    #[doc = "Set forwarding unregistered methods on the passthrough allowlist to the rollup"]
+    #[clap(long = "is-using-passthrough")]
+    pub is_using_passthrough: Option<bool>,
+
+    #[doc = "Set the method patterns forwarded to the rollup (comma separated, * suffix for prefixes)"]
+    #[clap(long = "passthrough-allow-list")]
+    pub passthrough_allow_list: Option<String>,
+
+    #[doc = "Set the method patterns never forwarded to the rollup (comma separated, * suffix for prefixes)"]
+    #[clap(long = "passthrough-deny-list")]
+    pub passthrough_deny_list: Option<String>,
+
+    #[doc = "Set caching rollup responses to immutable eth queries"]
+    #[clap(long = "is-using-response-cache")]
+    pub is_using_response_cache: Option<bool>,
+
+    #[doc = "Set the maximum number of cached rollup responses"]
+    #[clap(long = "response-cache-capacity")]
+    pub response_cache_capacity: Option<usize>,
+
+    #[doc = "Set how long block number and gas price responses are cached in milliseconds"]
+    #[clap(long = "response-cache-ttl-ms")]
+    pub response_cache_ttl_ms: Option<u64>,
+
+    #[doc = "Set the rollup rpc urls to balance between (comma separated, the rollup rpc url if empty)"]
+    #[clap(long = "rollup-rpc-url-list")]
+    pub rollup_rpc_url_list: Option<String>,
+
+    #[doc = "Set the weights of the rollup rpc urls (comma separated, one per rollup rpc url or empty for equal weights)"]
+    #[clap(long = "rollup-rpc-weight-list")]
+    pub rollup_rpc_weight_list: Option<String>,
+
+    #[doc = "Set how a rollup rpc url is selected (weighted_round_robin or latency)"]
+    #[clap(long = "rollup-selection")]
+    pub rollup_selection: Option<String>,
+
+    #[doc = "Set how many blocks a rollup rpc url may trail the highest one before it is skipped"]
+    #[clap(long = "rollup-max-block-lag")]
+    pub rollup_max_block_lag: Option<u64>,
+
+    #[doc = "Set the rollup health check interval in milliseconds"]
+    #[clap(long = "rollup-health-check-interval-ms")]
+    pub rollup_health_check_interval_ms: Option<u64>,
+
+    #[doc = "Set the consecutive failures after which a rollup rpc url is ejected"]
+    #[clap(long = "rollup-unhealthy-threshold")]
+    pub rollup_unhealthy_threshold: Option<u32>,
+
+    #[doc = "Set the consecutive successes after which an ejected rollup rpc url is re-admitted"]
+    #[clap(long = "rollup-healthy-threshold")]
+    pub rollup_healthy_threshold: Option<u32>,
+
+    #[doc = "Set hosting the rollups of rollup_profiles.toml next to the configured one"]
+    #[clap(long = "is-using-rollup-profiles")]
+    pub is_using_rollup_profiles: Option<bool>,
+
+    #[doc = "Set the timeout of a single rollup request attempt in milliseconds"]
+    #[clap(long = "rollup-attempt-timeout-ms")]
+    pub rollup_attempt_timeout_ms: Option<u64>,
+
+    #[doc = "Set the maximum number of encryption and decryption keys cached from the distributed key generation node"]
+    #[clap(long = "key-cache-capacity")]
+    pub key_cache_capacity: Option<usize>,
+
+    #[doc = "Set how long a key the distributed key generation node has not released yet is not requested again in milliseconds"]
+    #[clap(long = "unavailable-key-ttl-ms")]
+    pub unavailable_key_ttl_ms: Option<u64>,
+
+    #[doc = "Set how long submissions are kept in the submission journal in milliseconds"]
+    #[clap(long = "submission-journal-retention-ms")]
+    pub submission_journal_retention_ms: Option<u64>,
+
+    #[doc = "Set the maximum number of submissions kept in the submission journal"]
+    #[clap(long = "submission-journal-capacity")]
+    pub submission_journal_capacity: Option<usize>,
 }
 
 impl Default for ConfigOption {
@@ -65,6 +299,54 @@ impl Default for ConfigOption {
             distributed_key_generation_rpc_url: Some(
                 DEFAULT_DISTRIBUTED_KEY_GENERATION_RPC_URL.into(),
             ),
+            encryption_key_refresh_interval_ms: Some(DEFAULT_ENCRYPTION_KEY_REFRESH_INTERVAL_MS),
+            encryption_key_max_age_ms: Some(DEFAULT_ENCRYPTION_KEY_MAX_AGE_MS),
+            sequencer_max_attempts: Some(DEFAULT_SEQUENCER_MAX_ATTEMPTS),
+            sequencer_attempt_timeout_ms: Some(DEFAULT_SEQUENCER_ATTEMPT_TIMEOUT_MS),
+            sequencer_retry_backoff_ms: Some(DEFAULT_SEQUENCER_RETRY_BACKOFF_MS),
+            sequencer_health_check_interval_ms: Some(DEFAULT_SEQUENCER_HEALTH_CHECK_INTERVAL_MS),
+            sequencer_unhealthy_threshold: Some(DEFAULT_SEQUENCER_UNHEALTHY_THRESHOLD),
+            sequencer_healthy_threshold: Some(DEFAULT_SEQUENCER_HEALTHY_THRESHOLD),
+            internal_rpc_url: Some(DEFAULT_INTERNAL_RPC_URL.into()),
+            metrics_url: Some(DEFAULT_METRICS_URL.into()),
+            is_using_rate_limit: Some(DEFAULT_IS_USING_RATE_LIMIT),
+            rate_limit_read_requests_per_second: Some(DEFAULT_RATE_LIMIT_READ_REQUESTS_PER_SECOND),
+            rate_limit_read_burst: Some(DEFAULT_RATE_LIMIT_READ_BURST),
+            rate_limit_expensive_requests_per_second: Some(
+                DEFAULT_RATE_LIMIT_EXPENSIVE_REQUESTS_PER_SECOND,
+            ),
+            rate_limit_expensive_burst: Some(DEFAULT_RATE_LIMIT_EXPENSIVE_BURST),
+            is_rate_limiting_by_api_key: Some(DEFAULT_IS_RATE_LIMITING_BY_API_KEY),
+            is_using_api_key_auth: Some(DEFAULT_IS_USING_API_KEY_AUTH),
+            is_using_transaction_validation: Some(DEFAULT_IS_USING_TRANSACTION_VALIDATION),
+            max_raw_transaction_size: Some(DEFAULT_MAX_RAW_TRANSACTION_SIZE),
+            max_transaction_gas_limit: Some(DEFAULT_MAX_TRANSACTION_GAS_LIMIT),
+            is_using_account_precheck: Some(DEFAULT_IS_USING_ACCOUNT_PRECHECK),
+            sequencer_address_list: Some(DEFAULT_SEQUENCER_ADDRESS_LIST.into()),
+            order_commitment_verification: Some(DEFAULT_ORDER_COMMITMENT_VERIFICATION.into()),
+            is_using_websocket: Some(DEFAULT_IS_USING_WEBSOCKET),
+            rollup_websocket_url: Some(DEFAULT_ROLLUP_WEBSOCKET_URL.into()),
+            websocket_max_subscriptions: Some(DEFAULT_WEBSOCKET_MAX_SUBSCRIPTIONS),
+            max_batch_size: Some(DEFAULT_MAX_BATCH_SIZE),
+            is_using_passthrough: Some(DEFAULT_IS_USING_PASSTHROUGH),
+            passthrough_allow_list: Some(DEFAULT_PASSTHROUGH_ALLOW_LIST.into()),
+            passthrough_deny_list: Some(DEFAULT_PASSTHROUGH_DENY_LIST.into()),
+            is_using_response_cache: Some(DEFAULT_IS_USING_RESPONSE_CACHE),
+            response_cache_capacity: Some(DEFAULT_RESPONSE_CACHE_CAPACITY),
+            response_cache_ttl_ms: Some(DEFAULT_RESPONSE_CACHE_TTL_MS),
+            rollup_rpc_url_list: Some(DEFAULT_ROLLUP_RPC_URL_LIST.into()),
+            rollup_rpc_weight_list: Some(DEFAULT_ROLLUP_RPC_WEIGHT_LIST.into()),
+            rollup_selection: Some(DEFAULT_ROLLUP_SELECTION.into()),
+            rollup_max_block_lag: Some(DEFAULT_ROLLUP_MAX_BLOCK_LAG),
+            rollup_health_check_interval_ms: Some(DEFAULT_ROLLUP_HEALTH_CHECK_INTERVAL_MS),
+            rollup_unhealthy_threshold: Some(DEFAULT_ROLLUP_UNHEALTHY_THRESHOLD),
+            rollup_healthy_threshold: Some(DEFAULT_ROLLUP_HEALTHY_THRESHOLD),
+            is_using_rollup_profiles: Some(DEFAULT_IS_USING_ROLLUP_PROFILES),
+            rollup_attempt_timeout_ms: Some(DEFAULT_ROLLUP_ATTEMPT_TIMEOUT_MS),
+            key_cache_capacity: Some(DEFAULT_KEY_CACHE_CAPACITY),
+            unavailable_key_ttl_ms: Some(DEFAULT_UNAVAILABLE_KEY_TTL_MS),
+            submission_journal_retention_ms: Some(DEFAULT_SUBMISSION_JOURNAL_RETENTION_MS),
+            submission_journal_capacity: Some(DEFAULT_SUBMISSION_JOURNAL_CAPACITY),
         }
     }
 }
@@ -136,6 +418,432 @@ impl ConfigOption {
             &self.distributed_key_generation_rpc_url,
         );
 
+        set_toml_comment(
+            &mut toml_string,
+            "Set the interval (ms) to refresh the cached encryption key",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "encryption_key_refresh_interval_ms",
+            &self.encryption_key_refresh_interval_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum age (ms) of the cached encryption key",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "encryption_key_max_age_ms",
+            &self.encryption_key_max_age_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum number of attempts to send a request to the sequencers",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_max_attempts",
+            &self.sequencer_max_attempts,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the timeout (ms) of a single request to a sequencer",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_attempt_timeout_ms",
+            &self.sequencer_attempt_timeout_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the backoff (ms) before retrying a request on another sequencer",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_retry_backoff_ms",
+            &self.sequencer_retry_backoff_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the interval (ms) to check the health of the sequencers",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_health_check_interval_ms",
+            &self.sequencer_health_check_interval_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the number of consecutive failures to eject a sequencer",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_unhealthy_threshold",
+            &self.sequencer_unhealthy_threshold,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the number of consecutive successes to re-admit a sequencer",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_healthy_threshold",
+            &self.sequencer_healthy_threshold,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the internal rpc url served behind the gateway",
+        );
+        set_toml_name_value(&mut toml_string, "internal_rpc_url", &self.internal_rpc_url);
+
+        set_toml_comment(&mut toml_string, "Set the metrics url");
+        set_toml_name_value(&mut toml_string, "metrics_url", &self.metrics_url);
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set using rate limiting on the external rpc url",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_rate_limit",
+            &self.is_using_rate_limit,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the read requests per second allowed per client",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rate_limit_read_requests_per_second",
+            &self.rate_limit_read_requests_per_second,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the read request burst allowed per client",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rate_limit_read_burst",
+            &self.rate_limit_read_burst,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the encryption and submission requests per second allowed per client",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rate_limit_expensive_requests_per_second",
+            &self.rate_limit_expensive_requests_per_second,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the encryption and submission request burst allowed per client",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rate_limit_expensive_burst",
+            &self.rate_limit_expensive_burst,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set rate limiting clients by the bearer api key instead of the ip address",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_rate_limiting_by_api_key",
+            &self.is_rate_limiting_by_api_key,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set using api key authentication with the api keys file in the config path",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_api_key_auth",
+            &self.is_using_api_key_auth,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set validating raw transactions before encryption and submission",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_transaction_validation",
+            &self.is_using_transaction_validation,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum size of a raw transaction in bytes",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "max_raw_transaction_size",
+            &self.max_raw_transaction_size,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum gas limit of a transaction",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "max_transaction_gas_limit",
+            &self.max_transaction_gas_limit,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set checking the sender nonce and balance against the rollup before sequencing",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_account_precheck",
+            &self.is_using_account_precheck,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the cluster sequencer addresses allowed to sign order commitments",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "sequencer_address_list",
+            &self.sequencer_address_list,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the order commitment verification (disabled, flag or reject)",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "order_commitment_verification",
+            &self.order_commitment_verification,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set serving websocket connections on the external rpc url",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_websocket",
+            &self.is_using_websocket,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the rollup websocket url subscriptions are proxied to",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_websocket_url",
+            &self.rollup_websocket_url,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum number of subscriptions per websocket connection",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "websocket_max_subscriptions",
+            &self.websocket_max_subscriptions,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum number of calls in a batch request",
+        );
+        set_toml_name_value(&mut toml_string, "max_batch_size", &self.max_batch_size);
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set forwarding unregistered methods on the passthrough allowlist to the rollup",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_passthrough",
+            &self.is_using_passthrough,
+        );
+
+        set_toml_comment(&mut toml_string, "Set the method patterns forwarded to the rollup (comma separated, * suffix for prefixes)");
+        set_toml_name_value(
+            &mut toml_string,
+            "passthrough_allow_list",
+            &self.passthrough_allow_list,
+        );
+
+        set_toml_comment(&mut toml_string, "Set the method patterns never forwarded to the rollup (comma separated, * suffix for prefixes)");
+        set_toml_name_value(
+            &mut toml_string,
+            "passthrough_deny_list",
+            &self.passthrough_deny_list,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set caching rollup responses to immutable eth queries",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_response_cache",
+            &self.is_using_response_cache,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum number of cached rollup responses",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "response_cache_capacity",
+            &self.response_cache_capacity,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set how long block number and gas price responses are cached in milliseconds",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "response_cache_ttl_ms",
+            &self.response_cache_ttl_ms,
+        );
+
+        set_toml_comment(&mut toml_string, "Set the rollup rpc urls to balance between (comma separated, the rollup rpc url if empty)");
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_rpc_url_list",
+            &self.rollup_rpc_url_list,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the weights of the rollup rpc urls (comma separated, one per rollup rpc url or empty for equal weights)",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_rpc_weight_list",
+            &self.rollup_rpc_weight_list,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set how a rollup rpc url is selected (weighted_round_robin or latency)",
+        );
+        set_toml_name_value(&mut toml_string, "rollup_selection", &self.rollup_selection);
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set how many blocks a rollup rpc url may trail the highest one before it is skipped",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_max_block_lag",
+            &self.rollup_max_block_lag,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the rollup health check interval in milliseconds",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_health_check_interval_ms",
+            &self.rollup_health_check_interval_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the consecutive failures after which a rollup rpc url is ejected",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_unhealthy_threshold",
+            &self.rollup_unhealthy_threshold,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the consecutive successes after which an ejected rollup rpc url is re-admitted",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_healthy_threshold",
+            &self.rollup_healthy_threshold,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set hosting the rollups of rollup_profiles.toml next to the configured one",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "is_using_rollup_profiles",
+            &self.is_using_rollup_profiles,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the timeout of a single rollup request attempt in milliseconds",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "rollup_attempt_timeout_ms",
+            &self.rollup_attempt_timeout_ms,
+        );
+
+        set_toml_comment(&mut toml_string, "Set the maximum number of encryption and decryption keys cached from the distributed key generation node");
+        set_toml_name_value(
+            &mut toml_string,
+            "key_cache_capacity",
+            &self.key_cache_capacity,
+        );
+
+        set_toml_comment(&mut toml_string, "Set how long a key the distributed key generation node has not released yet is not requested again in milliseconds");
+        set_toml_name_value(
+            &mut toml_string,
+            "unavailable_key_ttl_ms",
+            &self.unavailable_key_ttl_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set how long submissions are kept in the submission journal in milliseconds",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "submission_journal_retention_ms",
+            &self.submission_journal_retention_ms,
+        );
+
+        set_toml_comment(
+            &mut toml_string,
+            "Set the maximum number of submissions kept in the submission journal",
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "submission_journal_capacity",
+            &self.submission_journal_capacity,
+        );
+
         toml_string
     }
 
@@ -180,6 +888,232 @@ impl ConfigOption {
                 .clone_from(&other.distributed_key_generation_rpc_url);
         }
 
+        if other.encryption_key_refresh_interval_ms.is_some() {
+            self.encryption_key_refresh_interval_ms
+                .clone_from(&other.encryption_key_refresh_interval_ms);
+        }
+
+        if other.encryption_key_max_age_ms.is_some() {
+            self.encryption_key_max_age_ms
+                .clone_from(&other.encryption_key_max_age_ms);
+        }
+
+        if other.sequencer_max_attempts.is_some() {
+            self.sequencer_max_attempts
+                .clone_from(&other.sequencer_max_attempts);
+        }
+
+        if other.sequencer_attempt_timeout_ms.is_some() {
+            self.sequencer_attempt_timeout_ms
+                .clone_from(&other.sequencer_attempt_timeout_ms);
+        }
+
+        if other.sequencer_retry_backoff_ms.is_some() {
+            self.sequencer_retry_backoff_ms
+                .clone_from(&other.sequencer_retry_backoff_ms);
+        }
+
+        if other.sequencer_health_check_interval_ms.is_some() {
+            self.sequencer_health_check_interval_ms
+                .clone_from(&other.sequencer_health_check_interval_ms);
+        }
+
+        if other.sequencer_unhealthy_threshold.is_some() {
+            self.sequencer_unhealthy_threshold
+                .clone_from(&other.sequencer_unhealthy_threshold);
+        }
+
+        if other.sequencer_healthy_threshold.is_some() {
+            self.sequencer_healthy_threshold
+                .clone_from(&other.sequencer_healthy_threshold);
+        }
+
+        if other.internal_rpc_url.is_some() {
+            self.internal_rpc_url.clone_from(&other.internal_rpc_url);
+        }
+
+        if other.metrics_url.is_some() {
+            self.metrics_url.clone_from(&other.metrics_url);
+        }
+
+        if other.is_using_rate_limit.is_some() {
+            self.is_using_rate_limit
+                .clone_from(&other.is_using_rate_limit);
+        }
+
+        if other.rate_limit_read_requests_per_second.is_some() {
+            self.rate_limit_read_requests_per_second
+                .clone_from(&other.rate_limit_read_requests_per_second);
+        }
+
+        if other.rate_limit_read_burst.is_some() {
+            self.rate_limit_read_burst
+                .clone_from(&other.rate_limit_read_burst);
+        }
+
+        if other.rate_limit_expensive_requests_per_second.is_some() {
+            self.rate_limit_expensive_requests_per_second
+                .clone_from(&other.rate_limit_expensive_requests_per_second);
+        }
+
+        if other.rate_limit_expensive_burst.is_some() {
+            self.rate_limit_expensive_burst
+                .clone_from(&other.rate_limit_expensive_burst);
+        }
+
+        if other.is_rate_limiting_by_api_key.is_some() {
+            self.is_rate_limiting_by_api_key
+                .clone_from(&other.is_rate_limiting_by_api_key);
+        }
+
+        if other.is_using_api_key_auth.is_some() {
+            self.is_using_api_key_auth
+                .clone_from(&other.is_using_api_key_auth);
+        }
+
+        if other.is_using_transaction_validation.is_some() {
+            self.is_using_transaction_validation
+                .clone_from(&other.is_using_transaction_validation);
+        }
+
+        if other.max_raw_transaction_size.is_some() {
+            self.max_raw_transaction_size
+                .clone_from(&other.max_raw_transaction_size);
+        }
+
+        if other.max_transaction_gas_limit.is_some() {
+            self.max_transaction_gas_limit
+                .clone_from(&other.max_transaction_gas_limit);
+        }
+
+        if other.is_using_account_precheck.is_some() {
+            self.is_using_account_precheck
+                .clone_from(&other.is_using_account_precheck);
+        }
+
+        if other.sequencer_address_list.is_some() {
+            self.sequencer_address_list
+                .clone_from(&other.sequencer_address_list);
+        }
+
+        if other.order_commitment_verification.is_some() {
+            self.order_commitment_verification
+                .clone_from(&other.order_commitment_verification);
+        }
+
+        if other.is_using_websocket.is_some() {
+            self.is_using_websocket
+                .clone_from(&other.is_using_websocket);
+        }
+
+        if other.rollup_websocket_url.is_some() {
+            self.rollup_websocket_url
+                .clone_from(&other.rollup_websocket_url);
+        }
+
+        if other.websocket_max_subscriptions.is_some() {
+            self.websocket_max_subscriptions
+                .clone_from(&other.websocket_max_subscriptions);
+        }
+
+        if other.max_batch_size.is_some() {
+            self.max_batch_size.clone_from(&other.max_batch_size);
+        }
+
+        if other.is_using_passthrough.is_some() {
+            self.is_using_passthrough
+                .clone_from(&other.is_using_passthrough);
+        }
+
+        if other.passthrough_allow_list.is_some() {
+            self.passthrough_allow_list
+                .clone_from(&other.passthrough_allow_list);
+        }
+
+        if other.passthrough_deny_list.is_some() {
+            self.passthrough_deny_list
+                .clone_from(&other.passthrough_deny_list);
+        }
+
+        if other.is_using_response_cache.is_some() {
+            self.is_using_response_cache
+                .clone_from(&other.is_using_response_cache);
+        }
+
+        if other.response_cache_capacity.is_some() {
+            self.response_cache_capacity
+                .clone_from(&other.response_cache_capacity);
+        }
+
+        if other.response_cache_ttl_ms.is_some() {
+            self.response_cache_ttl_ms
+                .clone_from(&other.response_cache_ttl_ms);
+        }
+
+        if other.rollup_rpc_url_list.is_some() {
+            self.rollup_rpc_url_list
+                .clone_from(&other.rollup_rpc_url_list);
+        }
+
+        if other.rollup_rpc_weight_list.is_some() {
+            self.rollup_rpc_weight_list
+                .clone_from(&other.rollup_rpc_weight_list);
+        }
+
+        if other.rollup_selection.is_some() {
+            self.rollup_selection.clone_from(&other.rollup_selection);
+        }
+
+        if other.rollup_max_block_lag.is_some() {
+            self.rollup_max_block_lag
+                .clone_from(&other.rollup_max_block_lag);
+        }
+
+        if other.rollup_health_check_interval_ms.is_some() {
+            self.rollup_health_check_interval_ms
+                .clone_from(&other.rollup_health_check_interval_ms);
+        }
+
+        if other.rollup_unhealthy_threshold.is_some() {
+            self.rollup_unhealthy_threshold
+                .clone_from(&other.rollup_unhealthy_threshold);
+        }
+
+        if other.rollup_healthy_threshold.is_some() {
+            self.rollup_healthy_threshold
+                .clone_from(&other.rollup_healthy_threshold);
+        }
+
+        if other.is_using_rollup_profiles.is_some() {
+            self.is_using_rollup_profiles
+                .clone_from(&other.is_using_rollup_profiles);
+        }
+
+        if other.rollup_attempt_timeout_ms.is_some() {
+            self.rollup_attempt_timeout_ms
+                .clone_from(&other.rollup_attempt_timeout_ms);
+        }
+
+        if other.key_cache_capacity.is_some() {
+            self.key_cache_capacity
+                .clone_from(&other.key_cache_capacity);
+        }
+
+        if other.unavailable_key_ttl_ms.is_some() {
+            self.unavailable_key_ttl_ms
+                .clone_from(&other.unavailable_key_ttl_ms);
+        }
+
+        if other.submission_journal_retention_ms.is_some() {
+            self.submission_journal_retention_ms
+                .clone_from(&other.submission_journal_retention_ms);
+        }
+
+        if other.submission_journal_capacity.is_some() {
+            self.submission_journal_capacity
+                .clone_from(&other.submission_journal_capacity);
+        }
+
         self
     }
 }
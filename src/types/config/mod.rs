@@ -1,19 +1,54 @@
 mod config_option;
 mod config_path;
+mod rollup_profile;
 
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, time::Duration};
 
 pub use config_option::ConfigOption;
+use config_option::{
+    DEFAULT_ENCRYPTION_KEY_MAX_AGE_MS, DEFAULT_ENCRYPTION_KEY_REFRESH_INTERVAL_MS,
+    DEFAULT_INTERNAL_RPC_URL, DEFAULT_IS_RATE_LIMITING_BY_API_KEY,
+    DEFAULT_IS_USING_ACCOUNT_PRECHECK, DEFAULT_IS_USING_API_KEY_AUTH, DEFAULT_IS_USING_PASSTHROUGH,
+    DEFAULT_IS_USING_RATE_LIMIT, DEFAULT_IS_USING_RESPONSE_CACHE, DEFAULT_IS_USING_ROLLUP_PROFILES,
+    DEFAULT_IS_USING_TRANSACTION_VALIDATION, DEFAULT_IS_USING_WEBSOCKET,
+    DEFAULT_KEY_CACHE_CAPACITY, DEFAULT_MAX_BATCH_SIZE, DEFAULT_MAX_RAW_TRANSACTION_SIZE,
+    DEFAULT_MAX_TRANSACTION_GAS_LIMIT, DEFAULT_METRICS_URL, DEFAULT_ORDER_COMMITMENT_VERIFICATION,
+    DEFAULT_PASSTHROUGH_ALLOW_LIST, DEFAULT_PASSTHROUGH_DENY_LIST,
+    DEFAULT_RATE_LIMIT_EXPENSIVE_BURST, DEFAULT_RATE_LIMIT_EXPENSIVE_REQUESTS_PER_SECOND,
+    DEFAULT_RATE_LIMIT_READ_BURST, DEFAULT_RATE_LIMIT_READ_REQUESTS_PER_SECOND,
+    DEFAULT_RESPONSE_CACHE_CAPACITY, DEFAULT_RESPONSE_CACHE_TTL_MS,
+    DEFAULT_ROLLUP_ATTEMPT_TIMEOUT_MS, DEFAULT_ROLLUP_HEALTHY_THRESHOLD,
+    DEFAULT_ROLLUP_HEALTH_CHECK_INTERVAL_MS, DEFAULT_ROLLUP_MAX_BLOCK_LAG,
+    DEFAULT_ROLLUP_RPC_URL_LIST, DEFAULT_ROLLUP_RPC_WEIGHT_LIST, DEFAULT_ROLLUP_SELECTION,
+    DEFAULT_ROLLUP_UNHEALTHY_THRESHOLD, DEFAULT_ROLLUP_WEBSOCKET_URL,
+    DEFAULT_SEQUENCER_ADDRESS_LIST, DEFAULT_SEQUENCER_ATTEMPT_TIMEOUT_MS,
+    DEFAULT_SEQUENCER_HEALTHY_THRESHOLD, DEFAULT_SEQUENCER_HEALTH_CHECK_INTERVAL_MS,
+    DEFAULT_SEQUENCER_MAX_ATTEMPTS, DEFAULT_SEQUENCER_RETRY_BACKOFF_MS,
+    DEFAULT_SEQUENCER_UNHEALTHY_THRESHOLD, DEFAULT_SUBMISSION_JOURNAL_CAPACITY,
+    DEFAULT_SUBMISSION_JOURNAL_RETENTION_MS, DEFAULT_UNAVAILABLE_KEY_TTL_MS,
+    DEFAULT_WEBSOCKET_MAX_SUBSCRIPTIONS,
+};
 pub use config_path::ConfigPath;
+pub use rollup_profile::RollupProfile;
+use rollup_profile::RollupProfileFile;
 use sequencer::types::EncryptedTransactionType;
 pub use serde::{Deserialize, Serialize};
 
+use crate::types::{OrderCommitmentVerification, RollupSelection};
+
 pub const DEFAULT_HOME_PATH: &str = ".secure-rpc";
 pub const LOG_DIR_NAME: &str = "logs";
 pub const CONFIG_FILE_NAME: &str = "Config.toml";
+pub const API_KEYS_FILE_NAME: &str = "api_keys.toml";
+pub const SUBMISSION_JOURNAL_FILE_NAME: &str = "submission_journal.jsonl";
+pub const ROLLUP_PROFILES_FILE_NAME: &str = "rollup_profiles.toml";
+pub const PVDE_PARAMS_DIR_NAME: &str = "pvde_params";
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
+    // Config directory
+    config_path: PathBuf,
+
     // Rollup ID
     rollup_id: String,
 
@@ -37,10 +72,234 @@ pub struct Config {
 
     // (when using SKDE)
     distributed_key_generation_rpc_url: String,
+
+    // Encryption key cache (when using SKDE)
+    encryption_key_refresh_interval_ms: u64,
+    encryption_key_max_age_ms: u64,
+
+    // Sequencer retry policy
+    sequencer_max_attempts: usize,
+    sequencer_attempt_timeout_ms: u64,
+    sequencer_retry_backoff_ms: u64,
+
+    // Sequencer health check
+    sequencer_health_check_interval_ms: u64,
+    sequencer_unhealthy_threshold: u32,
+    sequencer_healthy_threshold: u32,
+
+    // Internal RPC (behind the gateway)
+    internal_rpc_url: String,
+
+    // Metrics
+    metrics_url: String,
+
+    // Rate limiting
+    is_using_rate_limit: bool,
+    rate_limit_read_requests_per_second: u32,
+    rate_limit_read_burst: u32,
+    rate_limit_expensive_requests_per_second: u32,
+    rate_limit_expensive_burst: u32,
+    is_rate_limiting_by_api_key: bool,
+
+    // API key authentication
+    is_using_api_key_auth: bool,
+
+    // Transaction validation
+    is_using_transaction_validation: bool,
+    max_raw_transaction_size: usize,
+    max_transaction_gas_limit: u64,
+
+    // Account pre-check
+    is_using_account_precheck: bool,
+
+    // Order commitment verification
+    sequencer_address_list: Vec<String>,
+    order_commitment_verification: OrderCommitmentVerification,
+
+    // WebSocket
+    is_using_websocket: bool,
+    rollup_websocket_url: String,
+    websocket_max_subscriptions: usize,
+
+    // Batch
+    max_batch_size: usize,
+
+    // Passthrough
+    is_using_passthrough: bool,
+    passthrough_allow_list: Vec<String>,
+    passthrough_deny_list: Vec<String>,
+
+    // Response cache
+    is_using_response_cache: bool,
+    response_cache_capacity: usize,
+    response_cache_ttl_ms: u64,
+
+    // Rollup upstreams
+    rollup_rpc_url_list: Vec<String>,
+    rollup_rpc_weight_list: Vec<u32>,
+    rollup_selection: RollupSelection,
+    rollup_max_block_lag: u64,
+    rollup_health_check_interval_ms: u64,
+    rollup_unhealthy_threshold: u32,
+    rollup_healthy_threshold: u32,
+
+    // Rollup profiles
+    is_using_rollup_profiles: bool,
+
+    // Rollup requests
+    rollup_attempt_timeout_ms: u64,
+
+    // Distributed key generation key cache
+    key_cache_capacity: usize,
+    unavailable_key_ttl_ms: u64,
+
+    // Submission journal
+    submission_journal_retention_ms: u64,
+    submission_journal_capacity: usize,
 }
 
 impl Config {
     pub fn load(config_option: &mut ConfigOption) -> Result<Self, ConfigError> {
+        let (config_path, merged_config_option) = Self::load_config_option(config_option)?;
+
+        Self::from_config_option(config_path, merged_config_option)
+    }
+
+    /// Loads the profiles of the rollups hosted next to the configured one.
+    ///
+    /// Every `[[rollup]]` table of `rollup_profiles.toml` in the config path
+    /// overrides the options of the config file and the command line for its
+    /// rollup. The rollup is served on `/rollup/<rollup_id>` and, with `host`,
+    /// on `/` for requests with that `Host` header:
+    ///
+    /// ```toml
+    /// [[rollup]]
+    /// host = "rollup-b.example.com"
+    /// rollup_id = "rollup_b"
+    /// internal_rpc_url = "127.0.0.1:4001"
+    /// rollup_rpc_url = "http://127.0.0.1:9545"
+    /// sequencer_rpc_url_list = "http://127.0.0.1:4100"
+    /// encrypted_transaction_type = "skde"
+    /// distributed_key_generation_rpc_url = "http://127.0.0.1:7100"
+    /// ```
+    ///
+    /// The rollup id and the internal rpc url of every rollup must be unique.
+    /// The external rpc url and the metrics url are shared by all rollups.
+    ///
+    /// The rollup nodes and the sequencers of a rollup are never inherited from
+    /// the configured rollup, see [`Self::rollup_profile_config_option`].
+    pub fn load_rollup_profiles(
+        config_option: &mut ConfigOption,
+    ) -> Result<Vec<RollupProfile>, ConfigError> {
+        let (config_path, merged_config_option) = Self::load_config_option(config_option)?;
+
+        let rollup_profiles_path = config_path.join(ROLLUP_PROFILES_FILE_NAME);
+        let rollup_profiles_string =
+            fs::read_to_string(rollup_profiles_path).map_err(ConfigError::LoadRollupProfiles)?;
+        let rollup_profile_file: RollupProfileFile =
+            toml::from_str(&rollup_profiles_string).map_err(ConfigError::ParseRollupProfiles)?;
+
+        let mut rollup_id_list = vec![merged_config_option.rollup_id.clone()];
+        let mut internal_rpc_url_list = vec![merged_config_option.internal_rpc_url.clone()];
+
+        rollup_profile_file
+            .rollup_profile_list
+            .into_iter()
+            .map(|rollup_profile_option| {
+                let profile_config_option = Self::rollup_profile_config_option(
+                    &merged_config_option,
+                    &rollup_profile_option.config_option,
+                )?;
+
+                if rollup_id_list.contains(&profile_config_option.rollup_id) {
+                    return Err(ConfigError::DuplicateRollupId(
+                        profile_config_option.rollup_id.unwrap_or_default(),
+                    ));
+                }
+                if internal_rpc_url_list.contains(&profile_config_option.internal_rpc_url) {
+                    return Err(ConfigError::DuplicateInternalRpcUrl(
+                        profile_config_option.internal_rpc_url.unwrap_or_default(),
+                    ));
+                }
+                rollup_id_list.push(profile_config_option.rollup_id.clone());
+                internal_rpc_url_list.push(profile_config_option.internal_rpc_url.clone());
+
+                Ok(RollupProfile {
+                    host: rollup_profile_option.host,
+                    config: Self::from_config_option(config_path.clone(), profile_config_option)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the options of a rollup profile merged over the options of the
+    /// configured rollup, except for the rollup nodes and the sequencers.
+    ///
+    /// A profile must set `rollup_rpc_url` and `sequencer_rpc_url_list`, and
+    /// `rollup_websocket_url` with WebSocket enabled and
+    /// `sequencer_address_list` with order commitment verification enabled.
+    /// Its `rollup_rpc_url_list` and `rollup_rpc_weight_list` default to
+    /// `rollup_rpc_url` alone.
+    fn rollup_profile_config_option(
+        config_option: &ConfigOption,
+        profile_config_option: &ConfigOption,
+    ) -> Result<ConfigOption, ConfigError> {
+        let mut base_config_option = config_option.clone();
+        base_config_option.rollup_rpc_url = None;
+        base_config_option.rollup_rpc_url_list = None;
+        base_config_option.rollup_rpc_weight_list = None;
+        base_config_option.rollup_websocket_url = None;
+        base_config_option.sequencer_rpc_url_list = None;
+        base_config_option.sequencer_address_list = None;
+
+        let merged_config_option = base_config_option.merge(profile_config_option);
+
+        let is_using_websocket = merged_config_option
+            .is_using_websocket
+            .unwrap_or(DEFAULT_IS_USING_WEBSOCKET);
+        let is_verifying_order_commitment = merged_config_option
+            .order_commitment_verification
+            .as_deref()
+            .unwrap_or(DEFAULT_ORDER_COMMITMENT_VERIFICATION)
+            .parse::<OrderCommitmentVerification>()
+            .map_or(true, |verification| {
+                verification != OrderCommitmentVerification::Disabled
+            });
+
+        let required_option_list = [
+            (
+                "rollup_rpc_url",
+                merged_config_option.rollup_rpc_url.is_some(),
+            ),
+            (
+                "sequencer_rpc_url_list",
+                merged_config_option.sequencer_rpc_url_list.is_some(),
+            ),
+            (
+                "rollup_websocket_url",
+                !is_using_websocket || merged_config_option.rollup_websocket_url.is_some(),
+            ),
+            (
+                "sequencer_address_list",
+                !is_verifying_order_commitment
+                    || merged_config_option.sequencer_address_list.is_some(),
+            ),
+        ];
+        if let Some((option, _)) = required_option_list.into_iter().find(|(_, is_set)| !is_set) {
+            return Err(ConfigError::MissingRollupProfileOption {
+                rollup_id: merged_config_option.rollup_id.unwrap_or_default(),
+                option,
+            });
+        }
+
+        Ok(merged_config_option)
+    }
+
+    /// Returns the config path and the options of the config file merged with
+    /// the command line.
+    fn load_config_option(
+        config_option: &mut ConfigOption,
+    ) -> Result<(PathBuf, ConfigOption), ConfigError> {
         let config_path = match config_option.path.as_mut() {
             Some(config_path) => config_path.clone(),
             None => {
@@ -61,6 +320,13 @@ impl Config {
         // Merge configs from CLI input
         let merged_config_option = config_file.merge(config_option);
 
+        Ok((config_path, merged_config_option))
+    }
+
+    pub(crate) fn from_config_option(
+        config_path: PathBuf,
+        merged_config_option: ConfigOption,
+    ) -> Result<Self, ConfigError> {
         let encrypted_transaction_type = merged_config_option.encrypted_transaction_type.unwrap();
 
         let sequencer_rpc_url_list = merged_config_option
@@ -70,20 +336,258 @@ impl Config {
             .map(|s| s.trim().to_owned())
             .collect();
 
+        let sequencer_address_list = merged_config_option
+            .sequencer_address_list
+            .unwrap_or(DEFAULT_SEQUENCER_ADDRESS_LIST.into())
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let rollup_rpc_url = merged_config_option.rollup_rpc_url.unwrap();
+
+        let mut rollup_rpc_url_list: Vec<String> = merged_config_option
+            .rollup_rpc_url_list
+            .unwrap_or(DEFAULT_ROLLUP_RPC_URL_LIST.into())
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if rollup_rpc_url_list.is_empty() {
+            rollup_rpc_url_list.push(rollup_rpc_url.clone());
+        }
+
+        let rollup_rpc_weight_list: Vec<u32> = merged_config_option
+            .rollup_rpc_weight_list
+            .unwrap_or(DEFAULT_ROLLUP_RPC_WEIGHT_LIST.into())
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| match s.parse::<u32>() {
+                // A zero weight would never be selected.
+                Ok(weight) if weight > 0 => Ok(weight),
+                _ => Err(ConfigError::InvalidRollupRpcWeight(s.to_owned())),
+            })
+            .collect::<Result<_, _>>()?;
+        // Without weights every rollup rpc url weighs the same.
+        if !rollup_rpc_weight_list.is_empty()
+            && rollup_rpc_weight_list.len() != rollup_rpc_url_list.len()
+        {
+            return Err(ConfigError::RollupRpcWeightListLengthMismatch {
+                rollup_rpc_url_count: rollup_rpc_url_list.len(),
+                rollup_rpc_weight_count: rollup_rpc_weight_list.len(),
+            });
+        }
+
+        let encryption_key_refresh_interval_ms = merged_config_option
+            .encryption_key_refresh_interval_ms
+            .unwrap_or(DEFAULT_ENCRYPTION_KEY_REFRESH_INTERVAL_MS);
+        let encryption_key_max_age_ms = merged_config_option
+            .encryption_key_max_age_ms
+            .unwrap_or(DEFAULT_ENCRYPTION_KEY_MAX_AGE_MS);
+        if encryption_key_refresh_interval_ms == 0 {
+            return Err(ConfigError::InvalidEncryptionKeyRefreshInterval);
+        }
+        // Otherwise the cached key expires before the refresher renews it.
+        if encryption_key_max_age_ms <= encryption_key_refresh_interval_ms {
+            return Err(ConfigError::InvalidEncryptionKeyMaxAge {
+                encryption_key_max_age_ms,
+                encryption_key_refresh_interval_ms,
+            });
+        }
+
+        let is_rate_limiting_by_api_key = merged_config_option
+            .is_rate_limiting_by_api_key
+            .unwrap_or(DEFAULT_IS_RATE_LIMITING_BY_API_KEY);
+        let is_using_api_key_auth = merged_config_option
+            .is_using_api_key_auth
+            .unwrap_or(DEFAULT_IS_USING_API_KEY_AUTH);
+        // Without authentication any client could pick its own rate limit key.
+        if is_rate_limiting_by_api_key && !is_using_api_key_auth {
+            return Err(ConfigError::RateLimitingByApiKeyWithoutApiKeyAuth);
+        }
+
+        let rollup_selection = merged_config_option
+            .rollup_selection
+            .as_deref()
+            .unwrap_or(DEFAULT_ROLLUP_SELECTION)
+            .parse::<RollupSelection>()
+            .map_err(ConfigError::InvalidRollupSelection)?;
+
+        let order_commitment_verification = merged_config_option
+            .order_commitment_verification
+            .as_deref()
+            .unwrap_or(DEFAULT_ORDER_COMMITMENT_VERIFICATION)
+            .parse::<OrderCommitmentVerification>()
+            .map_err(ConfigError::InvalidOrderCommitmentVerification)?;
+
         Ok(Config {
+            config_path,
             rollup_id: merged_config_option.rollup_id.unwrap(),
             external_rpc_url: merged_config_option.external_rpc_url.unwrap(),
             sequencer_rpc_url_list: sequencer_rpc_url_list,
-            rollup_rpc_url: merged_config_option.rollup_rpc_url.unwrap(),
+            rollup_rpc_url,
             is_using_encryption: merged_config_option.is_using_encryption.unwrap(),
             is_using_zkp: merged_config_option.is_using_zkp.unwrap(),
             encrypted_transaction_type: EncryptedTransactionType::from(encrypted_transaction_type),
             distributed_key_generation_rpc_url: merged_config_option
                 .distributed_key_generation_rpc_url
                 .unwrap(),
+            encryption_key_refresh_interval_ms,
+            encryption_key_max_age_ms,
+            sequencer_max_attempts: merged_config_option
+                .sequencer_max_attempts
+                .unwrap_or(DEFAULT_SEQUENCER_MAX_ATTEMPTS),
+            sequencer_attempt_timeout_ms: merged_config_option
+                .sequencer_attempt_timeout_ms
+                .unwrap_or(DEFAULT_SEQUENCER_ATTEMPT_TIMEOUT_MS),
+            sequencer_retry_backoff_ms: merged_config_option
+                .sequencer_retry_backoff_ms
+                .unwrap_or(DEFAULT_SEQUENCER_RETRY_BACKOFF_MS),
+            sequencer_health_check_interval_ms: merged_config_option
+                .sequencer_health_check_interval_ms
+                .unwrap_or(DEFAULT_SEQUENCER_HEALTH_CHECK_INTERVAL_MS),
+            sequencer_unhealthy_threshold: merged_config_option
+                .sequencer_unhealthy_threshold
+                .unwrap_or(DEFAULT_SEQUENCER_UNHEALTHY_THRESHOLD),
+            sequencer_healthy_threshold: merged_config_option
+                .sequencer_healthy_threshold
+                .unwrap_or(DEFAULT_SEQUENCER_HEALTHY_THRESHOLD),
+            internal_rpc_url: merged_config_option
+                .internal_rpc_url
+                .unwrap_or_else(|| DEFAULT_INTERNAL_RPC_URL.into()),
+            metrics_url: merged_config_option
+                .metrics_url
+                .unwrap_or_else(|| DEFAULT_METRICS_URL.into()),
+            is_using_rate_limit: merged_config_option
+                .is_using_rate_limit
+                .unwrap_or(DEFAULT_IS_USING_RATE_LIMIT),
+            rate_limit_read_requests_per_second: merged_config_option
+                .rate_limit_read_requests_per_second
+                .unwrap_or(DEFAULT_RATE_LIMIT_READ_REQUESTS_PER_SECOND),
+            rate_limit_read_burst: merged_config_option
+                .rate_limit_read_burst
+                .unwrap_or(DEFAULT_RATE_LIMIT_READ_BURST),
+            rate_limit_expensive_requests_per_second: merged_config_option
+                .rate_limit_expensive_requests_per_second
+                .unwrap_or(DEFAULT_RATE_LIMIT_EXPENSIVE_REQUESTS_PER_SECOND),
+            rate_limit_expensive_burst: merged_config_option
+                .rate_limit_expensive_burst
+                .unwrap_or(DEFAULT_RATE_LIMIT_EXPENSIVE_BURST),
+            is_rate_limiting_by_api_key,
+            is_using_api_key_auth,
+            is_using_transaction_validation: merged_config_option
+                .is_using_transaction_validation
+                .unwrap_or(DEFAULT_IS_USING_TRANSACTION_VALIDATION),
+            max_raw_transaction_size: merged_config_option
+                .max_raw_transaction_size
+                .unwrap_or(DEFAULT_MAX_RAW_TRANSACTION_SIZE),
+            max_transaction_gas_limit: merged_config_option
+                .max_transaction_gas_limit
+                .unwrap_or(DEFAULT_MAX_TRANSACTION_GAS_LIMIT),
+            is_using_account_precheck: merged_config_option
+                .is_using_account_precheck
+                .unwrap_or(DEFAULT_IS_USING_ACCOUNT_PRECHECK),
+            sequencer_address_list,
+            order_commitment_verification,
+            is_using_websocket: merged_config_option
+                .is_using_websocket
+                .unwrap_or(DEFAULT_IS_USING_WEBSOCKET),
+            rollup_websocket_url: merged_config_option
+                .rollup_websocket_url
+                .unwrap_or(DEFAULT_ROLLUP_WEBSOCKET_URL.into()),
+            websocket_max_subscriptions: merged_config_option
+                .websocket_max_subscriptions
+                .unwrap_or(DEFAULT_WEBSOCKET_MAX_SUBSCRIPTIONS),
+            max_batch_size: merged_config_option
+                .max_batch_size
+                .unwrap_or(DEFAULT_MAX_BATCH_SIZE),
+            is_using_passthrough: merged_config_option
+                .is_using_passthrough
+                .unwrap_or(DEFAULT_IS_USING_PASSTHROUGH),
+            passthrough_allow_list: merged_config_option
+                .passthrough_allow_list
+                .unwrap_or(DEFAULT_PASSTHROUGH_ALLOW_LIST.into())
+                .split(',')
+                .map(|s| s.trim().to_owned())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            passthrough_deny_list: merged_config_option
+                .passthrough_deny_list
+                .unwrap_or(DEFAULT_PASSTHROUGH_DENY_LIST.into())
+                .split(',')
+                .map(|s| s.trim().to_owned())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            is_using_response_cache: merged_config_option
+                .is_using_response_cache
+                .unwrap_or(DEFAULT_IS_USING_RESPONSE_CACHE),
+            response_cache_capacity: merged_config_option
+                .response_cache_capacity
+                .unwrap_or(DEFAULT_RESPONSE_CACHE_CAPACITY),
+            response_cache_ttl_ms: merged_config_option
+                .response_cache_ttl_ms
+                .unwrap_or(DEFAULT_RESPONSE_CACHE_TTL_MS),
+            rollup_rpc_url_list,
+            rollup_rpc_weight_list,
+            rollup_selection,
+            rollup_max_block_lag: merged_config_option
+                .rollup_max_block_lag
+                .unwrap_or(DEFAULT_ROLLUP_MAX_BLOCK_LAG),
+            rollup_health_check_interval_ms: merged_config_option
+                .rollup_health_check_interval_ms
+                .unwrap_or(DEFAULT_ROLLUP_HEALTH_CHECK_INTERVAL_MS),
+            rollup_unhealthy_threshold: merged_config_option
+                .rollup_unhealthy_threshold
+                .unwrap_or(DEFAULT_ROLLUP_UNHEALTHY_THRESHOLD),
+            rollup_healthy_threshold: merged_config_option
+                .rollup_healthy_threshold
+                .unwrap_or(DEFAULT_ROLLUP_HEALTHY_THRESHOLD),
+            is_using_rollup_profiles: merged_config_option
+                .is_using_rollup_profiles
+                .unwrap_or(DEFAULT_IS_USING_ROLLUP_PROFILES),
+            rollup_attempt_timeout_ms: merged_config_option
+                .rollup_attempt_timeout_ms
+                .unwrap_or(DEFAULT_ROLLUP_ATTEMPT_TIMEOUT_MS),
+            key_cache_capacity: merged_config_option
+                .key_cache_capacity
+                .unwrap_or(DEFAULT_KEY_CACHE_CAPACITY),
+            unavailable_key_ttl_ms: merged_config_option
+                .unavailable_key_ttl_ms
+                .unwrap_or(DEFAULT_UNAVAILABLE_KEY_TTL_MS),
+            submission_journal_retention_ms: merged_config_option
+                .submission_journal_retention_ms
+                .unwrap_or(DEFAULT_SUBMISSION_JOURNAL_RETENTION_MS),
+            submission_journal_capacity: merged_config_option
+                .submission_journal_capacity
+                .unwrap_or(DEFAULT_SUBMISSION_JOURNAL_CAPACITY),
         })
     }
 
+    pub fn config_path(&self) -> &PathBuf {
+        &self.config_path
+    }
+
+    pub fn api_keys_path(&self) -> PathBuf {
+        self.config_path.join(API_KEYS_FILE_NAME)
+    }
+
+    pub fn rollup_profiles_path(&self) -> PathBuf {
+        self.config_path.join(ROLLUP_PROFILES_FILE_NAME)
+    }
+
+    pub fn submission_journal_path(&self) -> PathBuf {
+        self.config_path.join(SUBMISSION_JOURNAL_FILE_NAME)
+    }
+
+    /// Returns the directory of the PVDE parameters of the rollup, separate for
+    /// every hosted rollup.
+    pub fn pvde_params_path(&self) -> PathBuf {
+        self.config_path
+            .join(PVDE_PARAMS_DIR_NAME)
+            .join(&self.rollup_id)
+    }
+
     pub fn rollup_id(&self) -> &String {
         &self.rollup_id
     }
@@ -124,6 +628,208 @@ impl Config {
     pub fn distributed_key_generation_rpc_url(&self) -> &String {
         &self.distributed_key_generation_rpc_url
     }
+
+    pub fn encryption_key_refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.encryption_key_refresh_interval_ms)
+    }
+
+    pub fn encryption_key_max_age(&self) -> Duration {
+        Duration::from_millis(self.encryption_key_max_age_ms)
+    }
+
+    pub fn sequencer_max_attempts(&self) -> usize {
+        self.sequencer_max_attempts
+    }
+
+    pub fn sequencer_attempt_timeout(&self) -> Duration {
+        Duration::from_millis(self.sequencer_attempt_timeout_ms)
+    }
+
+    pub fn sequencer_retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.sequencer_retry_backoff_ms)
+    }
+
+    pub fn sequencer_health_check_interval(&self) -> Duration {
+        Duration::from_millis(self.sequencer_health_check_interval_ms)
+    }
+
+    pub fn sequencer_unhealthy_threshold(&self) -> u32 {
+        self.sequencer_unhealthy_threshold
+    }
+
+    pub fn sequencer_healthy_threshold(&self) -> u32 {
+        self.sequencer_healthy_threshold
+    }
+
+    pub fn internal_rpc_url(&self) -> &String {
+        &self.internal_rpc_url
+    }
+
+    pub fn internal_port(&self) -> Result<String, ConfigError> {
+        Ok(self
+            .internal_rpc_url()
+            .split(':')
+            .last()
+            .ok_or(ConfigError::InvalidInternalPort)?
+            .to_string())
+    }
+
+    pub fn metrics_url(&self) -> &String {
+        &self.metrics_url
+    }
+
+    pub fn metrics_port(&self) -> Result<String, ConfigError> {
+        Ok(self
+            .metrics_url()
+            .split(':')
+            .last()
+            .ok_or(ConfigError::InvalidMetricsPort)?
+            .to_string())
+    }
+
+    pub fn is_using_rate_limit(&self) -> bool {
+        self.is_using_rate_limit
+    }
+
+    pub fn rate_limit_read_requests_per_second(&self) -> u32 {
+        self.rate_limit_read_requests_per_second
+    }
+
+    pub fn rate_limit_read_burst(&self) -> u32 {
+        self.rate_limit_read_burst
+    }
+
+    pub fn rate_limit_expensive_requests_per_second(&self) -> u32 {
+        self.rate_limit_expensive_requests_per_second
+    }
+
+    pub fn rate_limit_expensive_burst(&self) -> u32 {
+        self.rate_limit_expensive_burst
+    }
+
+    pub fn is_rate_limiting_by_api_key(&self) -> bool {
+        self.is_rate_limiting_by_api_key
+    }
+
+    pub fn is_using_api_key_auth(&self) -> bool {
+        self.is_using_api_key_auth
+    }
+
+    pub fn is_using_transaction_validation(&self) -> bool {
+        self.is_using_transaction_validation
+    }
+
+    pub fn max_raw_transaction_size(&self) -> usize {
+        self.max_raw_transaction_size
+    }
+
+    pub fn max_transaction_gas_limit(&self) -> u64 {
+        self.max_transaction_gas_limit
+    }
+
+    pub fn is_using_account_precheck(&self) -> bool {
+        self.is_using_account_precheck
+    }
+
+    pub fn sequencer_address_list(&self) -> &Vec<String> {
+        &self.sequencer_address_list
+    }
+
+    pub fn order_commitment_verification(&self) -> OrderCommitmentVerification {
+        self.order_commitment_verification
+    }
+
+    pub fn is_using_websocket(&self) -> bool {
+        self.is_using_websocket
+    }
+
+    pub fn rollup_websocket_url(&self) -> &String {
+        &self.rollup_websocket_url
+    }
+
+    pub fn websocket_max_subscriptions(&self) -> usize {
+        self.websocket_max_subscriptions
+    }
+
+    pub fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    pub fn is_using_passthrough(&self) -> bool {
+        self.is_using_passthrough
+    }
+
+    pub fn passthrough_allow_list(&self) -> &Vec<String> {
+        &self.passthrough_allow_list
+    }
+
+    pub fn passthrough_deny_list(&self) -> &Vec<String> {
+        &self.passthrough_deny_list
+    }
+
+    pub fn is_using_response_cache(&self) -> bool {
+        self.is_using_response_cache
+    }
+
+    pub fn response_cache_capacity(&self) -> usize {
+        self.response_cache_capacity
+    }
+
+    pub fn response_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.response_cache_ttl_ms)
+    }
+
+    pub fn rollup_rpc_url_list(&self) -> &Vec<String> {
+        &self.rollup_rpc_url_list
+    }
+
+    pub fn rollup_rpc_weight_list(&self) -> &Vec<u32> {
+        &self.rollup_rpc_weight_list
+    }
+
+    pub fn rollup_selection(&self) -> RollupSelection {
+        self.rollup_selection
+    }
+
+    pub fn rollup_max_block_lag(&self) -> u64 {
+        self.rollup_max_block_lag
+    }
+
+    pub fn rollup_health_check_interval(&self) -> Duration {
+        Duration::from_millis(self.rollup_health_check_interval_ms)
+    }
+
+    pub fn rollup_unhealthy_threshold(&self) -> u32 {
+        self.rollup_unhealthy_threshold
+    }
+
+    pub fn rollup_healthy_threshold(&self) -> u32 {
+        self.rollup_healthy_threshold
+    }
+
+    pub fn is_using_rollup_profiles(&self) -> bool {
+        self.is_using_rollup_profiles
+    }
+
+    pub fn rollup_attempt_timeout(&self) -> Duration {
+        Duration::from_millis(self.rollup_attempt_timeout_ms)
+    }
+
+    pub fn key_cache_capacity(&self) -> usize {
+        self.key_cache_capacity
+    }
+
+    pub fn unavailable_key_ttl(&self) -> Duration {
+        Duration::from_millis(self.unavailable_key_ttl_ms)
+    }
+
+    pub fn submission_journal_retention(&self) -> Duration {
+        Duration::from_millis(self.submission_journal_retention_ms)
+    }
+
+    pub fn submission_journal_capacity(&self) -> usize {
+        self.submission_journal_capacity
+    }
 }
 
 #[derive(Debug)]
@@ -135,7 +841,32 @@ pub enum ConfigError {
     CreateConfigFile(std::io::Error),
     CreatePrivateKeyFile(std::io::Error),
     InvalidExternalPort,
+    InvalidInternalPort,
+    InvalidMetricsPort,
     InvalidClusterPort,
+    InvalidEncryptionKeyRefreshInterval,
+    InvalidEncryptionKeyMaxAge {
+        encryption_key_max_age_ms: u64,
+        encryption_key_refresh_interval_ms: u64,
+    },
+    LoadApiKeys(std::io::Error),
+    ParseApiKeys(toml::de::Error),
+    LoadRollupProfiles(std::io::Error),
+    ParseRollupProfiles(toml::de::Error),
+    DuplicateRollupId(String),
+    DuplicateInternalRpcUrl(String),
+    MissingRollupProfileOption {
+        rollup_id: String,
+        option: &'static str,
+    },
+    InvalidRollupRpcWeight(String),
+    InvalidRollupSelection(String),
+    RateLimitingByApiKeyWithoutApiKeyAuth,
+    InvalidOrderCommitmentVerification(String),
+    RollupRpcWeightListLengthMismatch {
+        rollup_rpc_url_count: usize,
+        rollup_rpc_weight_count: usize,
+    },
 }
 
 impl std::fmt::Display for ConfigError {
@@ -145,3 +876,158 @@ impl std::fmt::Display for ConfigError {
 }
 
 impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_config_option(option_list: &str) -> ConfigOption {
+        toml::from_str(option_list).unwrap()
+    }
+
+    #[test]
+    fn rollup_profiles_do_not_inherit_upstreams() {
+        let mut config_option = ConfigOption::default();
+        config_option.rollup_rpc_url_list =
+            Some("http://10.0.0.1:8545,http://10.0.0.2:8545".into());
+        config_option.rollup_rpc_weight_list = Some("1,2".into());
+        config_option.sequencer_address_list = Some("0x01".into());
+
+        let profile_config_option = profile_config_option(
+            r#"
+            rollup_id = "rollup_b"
+            internal_rpc_url = "127.0.0.1:4001"
+            rollup_rpc_url = "http://127.0.0.1:9545"
+            sequencer_rpc_url_list = "http://127.0.0.1:4100"
+            "#,
+        );
+
+        let merged_config_option =
+            Config::rollup_profile_config_option(&config_option, &profile_config_option).unwrap();
+        let config =
+            Config::from_config_option(std::env::temp_dir(), merged_config_option).unwrap();
+
+        assert_eq!(config.rollup_id(), "rollup_b");
+        assert_eq!(
+            config.rollup_rpc_url_list(),
+            &vec!["http://127.0.0.1:9545".to_owned()]
+        );
+        assert_eq!(
+            config.sequencer_rpc_url_list(),
+            &vec!["http://127.0.0.1:4100".to_owned()]
+        );
+        assert!(config.sequencer_address_list().is_empty());
+    }
+
+    #[test]
+    fn rollup_profiles_must_set_their_upstreams() {
+        let mut config_option = ConfigOption::default();
+        config_option.order_commitment_verification = Some("reject".into());
+
+        let profile_config_option = profile_config_option(
+            r#"
+            rollup_id = "rollup_b"
+            rollup_rpc_url = "http://127.0.0.1:9545"
+            "#,
+        );
+        assert!(matches!(
+            Config::rollup_profile_config_option(&config_option, &profile_config_option),
+            Err(ConfigError::MissingRollupProfileOption {
+                option: "sequencer_rpc_url_list",
+                ..
+            })
+        ));
+
+        let profile_config_option = profile_config_option(
+            r#"
+            rollup_id = "rollup_b"
+            rollup_rpc_url = "http://127.0.0.1:9545"
+            sequencer_rpc_url_list = "http://127.0.0.1:4100"
+            "#,
+        );
+        assert!(matches!(
+            Config::rollup_profile_config_option(&config_option, &profile_config_option),
+            Err(ConfigError::MissingRollupProfileOption {
+                option: "sequencer_address_list",
+                ..
+            })
+        ));
+    }
+
+    fn rollup_config_option(option_list: &str) -> ConfigOption {
+        ConfigOption::default().merge(&profile_config_option(option_list))
+    }
+
+    #[test]
+    fn rejects_unknown_rollup_selections() {
+        let config_option = rollup_config_option(r#"rollup_selection = "latancy""#);
+
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::InvalidRollupSelection(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_rollup_rpc_weights() {
+        let config_option = rollup_config_option(
+            r#"
+            rollup_rpc_url_list = "http://10.0.0.1:8545,http://10.0.0.2:8545"
+            rollup_rpc_weight_list = "1,0"
+            "#,
+        );
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::InvalidRollupRpcWeight(weight)) if weight == "0"
+        ));
+
+        let config_option = rollup_config_option(
+            r#"
+            rollup_rpc_url_list = "http://10.0.0.1:8545,http://10.0.0.2:8545"
+            rollup_rpc_weight_list = "1"
+            "#,
+        );
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::RollupRpcWeightListLengthMismatch {
+                rollup_rpc_url_count: 2,
+                rollup_rpc_weight_count: 1,
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_rate_limiting_by_api_key_without_api_key_auth() {
+        let config_option = rollup_config_option(
+            r#"
+            is_rate_limiting_by_api_key = true
+            is_using_api_key_auth = false
+            "#,
+        );
+
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::RateLimitingByApiKeyWithoutApiKeyAuth)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_encryption_key_refresh_options() {
+        let config_option = rollup_config_option("encryption_key_refresh_interval_ms = 0");
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::InvalidEncryptionKeyRefreshInterval)
+        ));
+
+        let config_option = rollup_config_option(
+            r#"
+            encryption_key_refresh_interval_ms = 1000
+            encryption_key_max_age_ms = 1000
+            "#,
+        );
+        assert!(matches!(
+            Config::from_config_option(std::env::temp_dir(), config_option),
+            Err(ConfigError::InvalidEncryptionKeyMaxAge { .. })
+        ));
+    }
+}
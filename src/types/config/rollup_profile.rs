@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigOption};
+
+/// A rollup hosted next to the configured one.
+#[derive(Clone, Debug)]
+pub struct RollupProfile {
+    /// The `Host` header routed to this rollup in addition to its
+    /// `/rollup/<rollup_id>` path.
+    pub host: Option<String>,
+    pub config: Config,
+}
+
+/// The contents of `rollup_profiles.toml`.
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct RollupProfileFile {
+    #[serde(rename = "rollup", default)]
+    pub rollup_profile_list: Vec<RollupProfileOption>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct RollupProfileOption {
+    pub host: Option<String>,
+    #[serde(flatten)]
+    pub config_option: ConfigOption,
+}
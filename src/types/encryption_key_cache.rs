@@ -0,0 +1,138 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::error::Error;
+
+/// Latest SKDE encryption key kept fresh by the encryption key refresher.
+pub struct EncryptionKeyCache {
+    inner: Arc<RwLock<EncryptionKeyCacheInner>>,
+}
+
+#[derive(Default)]
+struct EncryptionKeyCacheInner {
+    encryption_key: Option<CachedEncryptionKey>,
+    last_error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CachedEncryptionKey {
+    pub key_id: u64,
+    pub encryption_key: String,
+    /// The last time the distributed key generation node confirmed this key.
+    pub refreshed_at: Instant,
+}
+
+impl CachedEncryptionKey {
+    pub fn age(&self) -> Duration {
+        self.refreshed_at.elapsed()
+    }
+}
+
+impl Clone for EncryptionKeyCache {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for EncryptionKeyCache {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(EncryptionKeyCacheInner::default())),
+        }
+    }
+}
+
+impl EncryptionKeyCache {
+    /// Returns the cached encryption key unless it is missing or older than
+    /// `max_age`, in which case encrypting to it is refused.
+    pub fn get(&self, max_age: Duration) -> Result<CachedEncryptionKey, Error> {
+        let inner = self.inner.read().unwrap();
+
+        let encryption_key = inner
+            .encryption_key
+            .clone()
+            .ok_or(Error::EncryptionKeyUnavailable)?;
+
+        if encryption_key.age() > max_age {
+            tracing::warn!(
+                "Cached encryption key (key_id: {}) is stale: {:?}",
+                encryption_key.key_id,
+                encryption_key.age()
+            );
+            return Err(Error::EncryptionKeyExpired);
+        }
+
+        Ok(encryption_key)
+    }
+
+    /// Stores the latest encryption key and returns `true` if the key id changed.
+    pub fn update(&self, key_id: u64, encryption_key: String) -> bool {
+        let mut inner = self.inner.write().unwrap();
+
+        let is_changed = inner
+            .encryption_key
+            .as_ref()
+            .map(|cached| cached.key_id != key_id)
+            .unwrap_or(true);
+
+        inner.encryption_key = Some(CachedEncryptionKey {
+            key_id,
+            encryption_key,
+            refreshed_at: Instant::now(),
+        });
+        inner.last_error = None;
+
+        is_changed
+    }
+
+    pub fn set_last_error(&self, error: String) {
+        self.inner.write().unwrap().last_error = Some(error);
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.inner.read().unwrap().last_error.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn serves_a_fresh_key() {
+        let encryption_key_cache = EncryptionKeyCache::default();
+        assert!(matches!(
+            encryption_key_cache.get(MAX_AGE),
+            Err(Error::EncryptionKeyUnavailable)
+        ));
+
+        assert!(encryption_key_cache.update(1, "key-1".into()));
+        assert!(!encryption_key_cache.update(1, "key-1".into()));
+
+        let encryption_key = encryption_key_cache.get(MAX_AGE).unwrap();
+        assert_eq!(encryption_key.key_id, 1);
+        assert_eq!(encryption_key.encryption_key, "key-1");
+    }
+
+    #[test]
+    fn refuses_an_expired_key() {
+        let encryption_key_cache = EncryptionKeyCache::default();
+        encryption_key_cache.update(1, "key-1".into());
+        std::thread::sleep(Duration::from_millis(10));
+
+        assert!(matches!(
+            encryption_key_cache.get(Duration::from_millis(1)),
+            Err(Error::EncryptionKeyExpired)
+        ));
+
+        // A refresh confirms the key again.
+        encryption_key_cache.update(1, "key-1".into());
+        assert!(encryption_key_cache.get(MAX_AGE).is_ok());
+    }
+}
@@ -1 +1,8 @@
 pub mod config;
+mod encryption_key_cache;
+mod order_commitment_verification;
+mod rollup_selection;
+
+pub use encryption_key_cache::{CachedEncryptionKey, EncryptionKeyCache};
+pub use order_commitment_verification::OrderCommitmentVerification;
+pub use rollup_selection::RollupSelection;
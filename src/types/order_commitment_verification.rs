@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with order commitments that fail verification.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderCommitmentVerification {
+    /// Do not verify order commitments.
+    #[default]
+    Disabled,
+    /// Log and journal invalid order commitments but return them as is.
+    Flag,
+    /// Answer invalid order commitments with an error.
+    Reject,
+}
+
+/// Unknown values are an error rather than [`Self::Disabled`], so that a typo
+/// cannot silently turn the verification off.
+impl FromStr for OrderCommitmentVerification {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "disabled" => Ok(Self::Disabled),
+            "flag" => Ok(Self::Flag),
+            "reject" => Ok(Self::Reject),
+            _ => Err(value.to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_values() {
+        assert_eq!(
+            "disabled".parse(),
+            Ok(OrderCommitmentVerification::Disabled)
+        );
+        assert_eq!("flag".parse(), Ok(OrderCommitmentVerification::Flag));
+        assert_eq!("Reject".parse(), Ok(OrderCommitmentVerification::Reject));
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        assert_eq!(
+            "rejct".parse::<OrderCommitmentVerification>(),
+            Err("rejct".to_owned())
+        );
+        assert!("".parse::<OrderCommitmentVerification>().is_err());
+    }
+}
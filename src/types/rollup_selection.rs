@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How the rollup client picks the rollup rpc url to send a request to.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollupSelection {
+    /// Spread requests in proportion to the weight of each rollup rpc url.
+    #[default]
+    WeightedRoundRobin,
+    /// Prefer the rollup rpc url with the lowest average latency.
+    Latency,
+}
+
+/// Unknown values are an error rather than [`Self::WeightedRoundRobin`], so
+/// that a typo cannot silently change the selection.
+impl FromStr for RollupSelection {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "weighted_round_robin" => Ok(Self::WeightedRoundRobin),
+            "latency" => Ok(Self::Latency),
+            _ => Err(value.to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_values() {
+        assert_eq!(
+            "weighted_round_robin".parse(),
+            Ok(RollupSelection::WeightedRoundRobin)
+        );
+        assert_eq!("Latency".parse(), Ok(RollupSelection::Latency));
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        assert_eq!(
+            "latancy".parse::<RollupSelection>(),
+            Err("latancy".to_owned())
+        );
+        assert!("".parse::<RollupSelection>().is_err());
+    }
+}
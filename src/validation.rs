@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+
+use ethers_core::{
+    types::{transaction::eip2718::TypedTransaction, Address, U256},
+    utils::{hex, keccak256, rlp::Rlp},
+};
+use serde_json::{json, Value};
+
+use crate::{error::Error, rpc::eth, state::AppState};
+
+/// A decoded signed transaction and its recovered sender.
+#[derive(Clone, Debug)]
+pub struct ValidatedTransaction {
+    pub from: Address,
+    pub nonce: U256,
+    /// `None` for transactions without EIP-155 replay protection.
+    pub chain_id: Option<u64>,
+    pub gas_limit: U256,
+    /// `gas_limit * gas_price + value`, the most the sender can be charged.
+    pub max_cost: U256,
+}
+
+/// Rejects raw transactions that the rollup would never execute before any
+/// encryption, key generation or sequencer work is spent on them.
+///
+/// - `is_using_transaction_validation`: checks the encoding, signature, size,
+///   chain id and gas limit.
+/// - `is_using_account_precheck`: checks the nonce and balance of the sender
+///   against the pending state of the rollup.
+pub async fn validate_raw_transaction(
+    context: &AppState,
+    raw_transaction: &str,
+) -> Result<(), Error> {
+    validate_transaction_list(context, &[raw_transaction.to_owned()]).await
+}
+
+/// Validates the transactions of one submission together, so that the
+/// balance of a sender covers all of its transactions at once.
+async fn validate_transaction_list(
+    context: &AppState,
+    raw_transaction_list: &[String],
+) -> Result<(), Error> {
+    let is_using_transaction_validation = context.config().is_using_transaction_validation();
+    let is_using_account_precheck = context.config().is_using_account_precheck();
+
+    if !is_using_transaction_validation && !is_using_account_precheck {
+        return Ok(());
+    }
+
+    let max_size = context.config().max_raw_transaction_size();
+    let transaction_list = raw_transaction_list
+        .iter()
+        .map(|raw_transaction| decode_raw_transaction(raw_transaction, max_size))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if is_using_transaction_validation {
+        let expected_chain_id = rollup_chain_id(context).await?;
+        let max_gas_limit = context.config().max_transaction_gas_limit();
+
+        for transaction in &transaction_list {
+            check_transaction(transaction, expected_chain_id, max_gas_limit)?;
+        }
+    }
+
+    if is_using_account_precheck {
+        for (from, sender_transaction_list) in group_by_sender(&transaction_list) {
+            check_account(context, from, &sender_transaction_list).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_transaction(
+    transaction: &ValidatedTransaction,
+    expected_chain_id: u64,
+    max_gas_limit: u64,
+) -> Result<(), Error> {
+    if transaction.chain_id != Some(expected_chain_id) {
+        return Err(Error::ChainIdMismatch {
+            expected: expected_chain_id,
+            actual: transaction.chain_id,
+        });
+    }
+
+    if transaction.gas_limit > U256::from(max_gas_limit) {
+        return Err(Error::GasLimitTooHigh {
+            gas_limit: transaction.gas_limit.to_string(),
+            max_gas_limit,
+        });
+    }
+
+    Ok(())
+}
+
+fn group_by_sender(
+    transaction_list: &[ValidatedTransaction],
+) -> BTreeMap<Address, Vec<&ValidatedTransaction>> {
+    let mut sender_transaction_list: BTreeMap<Address, Vec<&ValidatedTransaction>> =
+        BTreeMap::new();
+
+    for transaction in transaction_list {
+        sender_transaction_list
+            .entry(transaction.from)
+            .or_default()
+            .push(transaction);
+    }
+
+    sender_transaction_list
+}
+
+/// Encrypted transactions are opaque to the sequencer, so a stale nonce or an
+/// unaffordable transaction would otherwise only surface after ordering.
+async fn check_account(
+    context: &AppState,
+    from: Address,
+    transaction_list: &[&ValidatedTransaction],
+) -> Result<(), Error> {
+    let pending_nonce: U256 = eth::forward(
+        "eth_getTransactionCount",
+        json!([from, "pending"]),
+        context.clone(),
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!("Failed to get the pending nonce: {:?}", error);
+        Error::AccountStateUnavailable
+    })?;
+
+    for transaction in transaction_list {
+        if transaction.nonce < pending_nonce {
+            return Err(Error::NonceTooLow {
+                nonce: transaction.nonce.to_string(),
+                pending_nonce: pending_nonce.to_string(),
+            });
+        }
+    }
+
+    let balance: U256 = eth::forward("eth_getBalance", json!([from, "pending"]), context.clone())
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to get the balance: {:?}", error);
+            Error::AccountStateUnavailable
+        })?;
+
+    check_balance(transaction_list, balance)
+}
+
+/// Checks that `balance` covers the total cost of the transactions of one
+/// sender.
+fn check_balance(transaction_list: &[&ValidatedTransaction], balance: U256) -> Result<(), Error> {
+    let max_cost = transaction_list
+        .iter()
+        .fold(U256::zero(), |max_cost, transaction| {
+            max_cost.saturating_add(transaction.max_cost)
+        });
+
+    if max_cost > balance {
+        return Err(Error::InsufficientBalance {
+            max_cost: max_cost.to_string(),
+            balance: balance.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parses a bundle, the JSON list of its signed raw transactions, and
+/// validates its transactions together.
+pub async fn validate_bundle(context: &AppState, raw_bundle: &str) -> Result<Vec<String>, Error> {
+    let raw_transaction_list = parse_bundle(raw_bundle)?;
+
+    validate_transaction_list(context, &raw_transaction_list).await?;
+
+    Ok(raw_transaction_list)
+}
+
+pub fn parse_bundle(raw_bundle: &str) -> Result<Vec<String>, Error> {
+    let raw_transaction_list: Vec<String> =
+        serde_json::from_str(raw_bundle).map_err(|_| Error::InvalidBundle)?;
+
+    if raw_transaction_list.is_empty() {
+        return Err(Error::InvalidBundle);
+    }
+
+    Ok(raw_transaction_list)
+}
+
+/// Returns the bundle hash, the keccak256 hash of the concatenated transaction
+/// hashes of the bundle.
+pub fn bundle_hash(raw_transaction_list: &[String]) -> Result<String, Error> {
+    let mut transaction_hashes = Vec::with_capacity(raw_transaction_list.len() * 32);
+
+    for raw_transaction in raw_transaction_list {
+        let bytes = hex::decode(raw_transaction).map_err(|_| Error::InvalidTransactionEncoding)?;
+        transaction_hashes.extend_from_slice(&keccak256(bytes));
+    }
+
+    Ok(format!("0x{}", hex::encode(keccak256(transaction_hashes))))
+}
+
+/// Rejects submissions for another rollup than the one served by `context`,
+/// so that a client cannot use the sequencers of one rollup for another.
+pub fn validate_rollup_id(context: &AppState, rollup_id: &str) -> Result<(), Error> {
+    if rollup_id != context.config().rollup_id() {
+        return Err(Error::InvalidParams(format!(
+            "rollup id {} is not served here",
+            rollup_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Decodes a hex encoded signed transaction and recovers its sender.
+pub fn decode_raw_transaction(
+    raw_transaction: &str,
+    max_size: usize,
+) -> Result<ValidatedTransaction, Error> {
+    let bytes = hex::decode(raw_transaction).map_err(|_| Error::InvalidTransactionEncoding)?;
+    if bytes.len() > max_size {
+        return Err(Error::TransactionTooLarge {
+            size: bytes.len(),
+            max_size,
+        });
+    }
+
+    let (transaction, signature) =
+        TypedTransaction::decode_signed(&Rlp::new(&bytes)).map_err(|error| {
+            tracing::debug!("Failed to decode the raw transaction: {:?}", error);
+            Error::InvalidTransactionEncoding
+        })?;
+
+    let from = signature
+        .recover(transaction.sighash())
+        .map_err(|_| Error::InvalidTransactionSignature)?;
+
+    let chain_id = transaction.chain_id().map(|chain_id| chain_id.as_u64());
+    let nonce = transaction.nonce().cloned().unwrap_or_default();
+    let gas_limit = transaction.gas().cloned().unwrap_or_default();
+    let gas_price = transaction.gas_price().unwrap_or_default();
+    let value = transaction.value().cloned().unwrap_or_default();
+
+    Ok(ValidatedTransaction {
+        from,
+        nonce,
+        chain_id,
+        gas_limit,
+        max_cost: gas_limit.saturating_mul(gas_price).saturating_add(value),
+    })
+}
+
+/// Returns the chain id of the rollup, which is fetched once and cached.
+async fn rollup_chain_id(context: &AppState) -> Result<u64, Error> {
+    if let Some(chain_id) = context.rollup_chain_id() {
+        return Ok(chain_id);
+    }
+
+    let chain_id: String = eth::forward("eth_chainId", Vec::<Value>::new(), context.clone())
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to get the rollup chain id: {:?}", error);
+            Error::RollupChainIdUnavailable
+        })?;
+
+    let chain_id = u64::from_str_radix(chain_id.trim_start_matches("0x"), 16)
+        .map_err(|_| Error::RollupChainIdUnavailable)?;
+    context.set_rollup_chain_id(chain_id);
+
+    Ok(chain_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_core::{
+        k256::ecdsa::SigningKey,
+        types::{Signature, TransactionRequest},
+        utils::secret_key_to_address,
+    };
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 1;
+    const MAX_SIZE: usize = 128 * 1024;
+    const MAX_GAS_LIMIT: u64 = 30_000_000;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[1; 32]).unwrap()
+    }
+
+    fn transaction(nonce: u64, gas_limit: u64) -> TypedTransaction {
+        TransactionRequest::new()
+            .to(Address::zero())
+            .nonce(nonce)
+            .value(1)
+            .gas(gas_limit)
+            .gas_price(1)
+            .chain_id(CHAIN_ID)
+            .into()
+    }
+
+    fn encode(transaction: &TypedTransaction, signature: &Signature) -> String {
+        format!("0x{}", hex::encode(transaction.rlp_signed(signature)))
+    }
+
+    fn sign(transaction: &TypedTransaction) -> String {
+        let (signature, recovery_id) = signing_key()
+            .sign_prehash_recoverable(transaction.sighash().as_bytes())
+            .unwrap();
+        let bytes = signature.to_bytes();
+
+        let signature = Signature {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&bytes[32..]),
+            v: recovery_id.to_byte() as u64 + 35 + CHAIN_ID * 2,
+        };
+
+        encode(transaction, &signature)
+    }
+
+    #[test]
+    fn decodes_a_signed_transaction() {
+        let raw_transaction = sign(&transaction(7, 21_000));
+
+        let transaction = decode_raw_transaction(&raw_transaction, MAX_SIZE).unwrap();
+
+        assert_eq!(transaction.from, secret_key_to_address(&signing_key()));
+        assert_eq!(transaction.nonce, U256::from(7));
+        assert_eq!(transaction.chain_id, Some(CHAIN_ID));
+        assert_eq!(transaction.gas_limit, U256::from(21_000));
+        assert_eq!(transaction.max_cost, U256::from(21_001));
+        assert!(check_transaction(&transaction, CHAIN_ID, MAX_GAS_LIMIT).is_ok());
+    }
+
+    #[test]
+    fn rejects_an_invalid_signature() {
+        let signature = Signature {
+            r: U256::zero(),
+            s: U256::zero(),
+            v: 35 + CHAIN_ID * 2,
+        };
+        let raw_transaction = encode(&transaction(0, 21_000), &signature);
+
+        assert!(matches!(
+            decode_raw_transaction(&raw_transaction, MAX_SIZE),
+            Err(Error::InvalidTransactionSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_an_oversize_transaction() {
+        let raw_transaction = sign(&transaction(0, 21_000));
+
+        assert!(matches!(
+            decode_raw_transaction(&raw_transaction, 16),
+            Err(Error::TransactionTooLarge { max_size: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_another_chain_id() {
+        let transaction = decode_raw_transaction(&sign(&transaction(0, 21_000)), MAX_SIZE).unwrap();
+
+        assert!(matches!(
+            check_transaction(&transaction, CHAIN_ID + 1, MAX_GAS_LIMIT),
+            Err(Error::ChainIdMismatch {
+                expected,
+                actual: Some(CHAIN_ID),
+            }) if expected == CHAIN_ID + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_a_gas_limit_over_the_cap() {
+        let transaction =
+            decode_raw_transaction(&sign(&transaction(0, MAX_GAS_LIMIT + 1)), MAX_SIZE).unwrap();
+
+        assert!(matches!(
+            check_transaction(&transaction, CHAIN_ID, MAX_GAS_LIMIT),
+            Err(Error::GasLimitTooHigh {
+                max_gas_limit: MAX_GAS_LIMIT,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn checks_the_balance_against_the_whole_bundle() {
+        let transaction_list = [
+            decode_raw_transaction(&sign(&transaction(0, 21_000)), MAX_SIZE).unwrap(),
+            decode_raw_transaction(&sign(&transaction(1, 21_000)), MAX_SIZE).unwrap(),
+        ];
+        let sender_transaction_list = group_by_sender(&transaction_list);
+        assert_eq!(sender_transaction_list.len(), 1);
+
+        let sender_transaction_list = &sender_transaction_list[&transaction_list[0].from];
+        assert!(check_balance(&sender_transaction_list[..1], U256::from(21_001)).is_ok());
+        assert!(matches!(
+            check_balance(sender_transaction_list, U256::from(21_001)),
+            Err(Error::InsufficientBalance { .. })
+        ));
+        assert!(check_balance(sender_transaction_list, U256::from(42_002)).is_ok());
+    }
+}